@@ -0,0 +1,57 @@
+//! Contains the [ChainConfig], the parts of the `geth` chain config of the L2 chain that the
+//! program executes L2 blocks with.
+
+use serde::{Deserialize, Serialize};
+
+/// The parts of the `geth` chain config of the L2 chain, which the host passes to the program with
+/// the boot info, that the program executes L2 blocks with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfig {
+    /// The OP Stack parameters of the chain, if it is an OP Stack chain.
+    #[serde(default)]
+    pub optimism: Option<OptimismConfig>,
+}
+
+/// The OP Stack parameters of a `geth` chain config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimismConfig {
+    /// The EIP-1559 elasticity multiplier.
+    pub eip1559_elasticity: u64,
+    /// The EIP-1559 base fee max change denominator.
+    pub eip1559_denominator: u64,
+    /// The EIP-1559 base fee max change denominator from Canyon on, if it differs.
+    #[serde(default)]
+    pub eip1559_denominator_canyon: Option<u64>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_chain_config_from_geth_json() {
+        let json = r#"{
+            "chainId": 10,
+            "londonBlock": 0,
+            "optimism": {
+                "eip1559Elasticity": 6,
+                "eip1559Denominator": 50,
+                "eip1559DenominatorCanyon": 250
+            }
+        }"#;
+        let config: ChainConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.optimism,
+            Some(OptimismConfig {
+                eip1559_elasticity: 6,
+                eip1559_denominator: 50,
+                eip1559_denominator_canyon: Some(250),
+            })
+        );
+
+        let config: ChainConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.optimism, None);
+    }
+}
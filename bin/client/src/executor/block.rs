@@ -0,0 +1,168 @@
+//! Contains the helpers that the [StatelessExecutor] assembles blocks with: the EIP-1559 base fee
+//! and the receipts of the transactions.
+//!
+//! [StatelessExecutor]: super::StatelessExecutor
+
+use alloc::vec::Vec;
+use alloy_consensus::Header;
+use alloy_primitives::{b256, Bloom, Log, B256};
+use alloy_rlp::Encodable;
+use anyhow::{anyhow, ensure, Result};
+use core::cmp::Ordering;
+
+/// The hash of an empty list of ommers, `keccak256(rlp([]))`.
+pub(crate) const EMPTY_OMMERS_HASH: B256 =
+    b256!("1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347");
+
+/// Computes the base fee of the block after `parent`, per EIP-1559 with the given elasticity
+/// multiplier and base fee max change denominator.
+pub(crate) fn next_base_fee(parent: &Header, elasticity: u64, denominator: u64) -> Result<u64> {
+    let base_fee = parent
+        .base_fee_per_gas
+        .ok_or_else(|| anyhow!("L2 block {} has no base fee", parent.number))?
+        as u128;
+    let gas_target = (parent.gas_limit / elasticity.max(1)) as u128;
+    let gas_used = parent.gas_used as u128;
+    ensure!(gas_target > 0 && denominator > 0, "Invalid EIP-1559 parameters");
+
+    let next = match gas_used.cmp(&gas_target) {
+        Ordering::Equal => base_fee,
+        Ordering::Greater => {
+            let delta = base_fee * (gas_used - gas_target) / gas_target / denominator as u128;
+            base_fee + delta.max(1)
+        }
+        Ordering::Less => {
+            let delta = base_fee * (gas_target - gas_used) / gas_target / denominator as u128;
+            base_fee.saturating_sub(delta)
+        }
+    };
+    u64::try_from(next).map_err(|_| anyhow!("Base fee {next} overflows"))
+}
+
+/// The receipt of a transaction, as it is committed to by the receipts root of its block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Receipt {
+    /// The EIP-2718 type of the transaction.
+    pub(crate) tx_type: u8,
+    /// Whether the transaction succeeded.
+    pub(crate) success: bool,
+    /// The gas used by the transactions of the block up to and including this one.
+    pub(crate) cumulative_gas_used: u64,
+    /// The logs that the transaction emitted.
+    pub(crate) logs: Vec<Log>,
+    /// The nonce of the sender of a deposit before it was executed, from Regolith on.
+    pub(crate) deposit_nonce: Option<u64>,
+    /// The version of a deposit receipt, from Canyon on.
+    pub(crate) deposit_receipt_version: Option<u64>,
+}
+
+impl Receipt {
+    /// Returns the bloom filter of the logs of the receipt.
+    pub(crate) fn bloom(&self) -> Bloom {
+        let mut bloom = Bloom::ZERO;
+        self.logs.iter().for_each(|log| bloom.accrue_log(log));
+        bloom
+    }
+
+    /// Encodes the receipt with the EIP-2718 envelope of its transaction type. The deposit nonce
+    /// and receipt version are appended to the fields of deposit receipts that have them.
+    pub(crate) fn encode_2718(&self) -> Vec<u8> {
+        let bloom = self.bloom();
+        let payload_length = self.success.length() +
+            self.cumulative_gas_used.length() +
+            bloom.length() +
+            self.logs.length() +
+            self.deposit_nonce.map_or(0, |nonce| nonce.length()) +
+            self.deposit_receipt_version.map_or(0, |version| version.length());
+
+        let mut out = Vec::with_capacity(payload_length + 5);
+        if self.tx_type != 0 {
+            out.push(self.tx_type);
+        }
+        alloy_rlp::Header { list: true, payload_length }.encode(&mut out);
+        self.success.encode(&mut out);
+        self.cumulative_gas_used.encode(&mut out);
+        bloom.encode(&mut out);
+        self.logs.encode(&mut out);
+        if let Some(nonce) = self.deposit_nonce {
+            nonce.encode(&mut out);
+        }
+        if let Some(version) = self.deposit_receipt_version {
+            version.encode(&mut out);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use alloy_primitives::{address, keccak256, Bytes, LogData};
+
+    #[test]
+    fn test_empty_ommers_hash() {
+        assert_eq!(keccak256([alloy_rlp::EMPTY_LIST_CODE]), EMPTY_OMMERS_HASH);
+    }
+
+    #[test]
+    fn test_next_base_fee() {
+        let parent = |gas_used| Header {
+            gas_limit: 30_000_000,
+            gas_used,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        // The gas target is 5M with an elasticity of 6.
+        assert_eq!(next_base_fee(&parent(5_000_000), 6, 50).unwrap(), 1_000_000_000);
+        assert_eq!(next_base_fee(&parent(30_000_000), 6, 50).unwrap(), 1_100_000_000);
+        assert_eq!(next_base_fee(&parent(30_000_000), 6, 250).unwrap(), 1_020_000_000);
+        assert_eq!(next_base_fee(&parent(0), 6, 50).unwrap(), 980_000_000);
+        assert_eq!(next_base_fee(&parent(0), 6, 250).unwrap(), 996_000_000);
+
+        // The base fee increases by at least 1.
+        let parent = Header { base_fee_per_gas: Some(7), ..parent(5_000_001) };
+        assert_eq!(next_base_fee(&parent, 6, 50).unwrap(), 8);
+
+        assert!(next_base_fee(&Header::default(), 6, 50).is_err());
+    }
+
+    #[test]
+    fn test_receipt_encoding() {
+        let log = Log {
+            address: address!("4200000000000000000000000000000000000016"),
+            data: LogData::new_unchecked(vec![B256::repeat_byte(1)], Bytes::from_static(&[2])),
+        };
+        let receipt = Receipt {
+            tx_type: 0x7e,
+            success: true,
+            cumulative_gas_used: 21_000,
+            logs: vec![log.clone()],
+            deposit_nonce: Some(3),
+            deposit_receipt_version: Some(1),
+        };
+        let encoded = receipt.encode_2718();
+
+        let mut bloom = Bloom::ZERO;
+        bloom.accrue_log(&log);
+        assert_eq!(receipt.bloom(), bloom);
+
+        // The envelope is the type, and then the list of the fields.
+        let mut fields = vec![];
+        true.encode(&mut fields);
+        21_000u64.encode(&mut fields);
+        bloom.encode(&mut fields);
+        vec![log].encode(&mut fields);
+        3u64.encode(&mut fields);
+        1u64.encode(&mut fields);
+        let mut expected = vec![0x7e];
+        alloy_rlp::Header { list: true, payload_length: fields.len() }.encode(&mut expected);
+        expected.extend_from_slice(&fields);
+        assert_eq!(encoded, expected);
+
+        // Legacy receipts have no envelope, and receipts of other transactions no deposit fields.
+        let legacy =
+            Receipt { tx_type: 0, deposit_nonce: None, deposit_receipt_version: None, ..receipt };
+        assert_eq!(legacy.encode_2718()[0], 0xf9);
+    }
+}
@@ -0,0 +1,97 @@
+//! Contains the [L2Executor] trait, which the program executes the derived L2 blocks with, and its
+//! implementations.
+
+use crate::ChainConfig;
+use alloc::{string::String, vec::Vec};
+use alloy_consensus::Header;
+use alloy_primitives::{Bytes, B256};
+use anyhow::{bail, Result};
+use core::fmt::{self, Display};
+use kona_derive::types::{PayloadAttributes, RollupConfig};
+use kona_preimage::{HintWriterClient, PreimageOracleClient};
+
+mod block;
+
+mod state;
+
+mod stateless;
+pub use stateless::StatelessExecutor;
+
+/// An L2 block that was built and executed by an [L2Executor].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutedBlock {
+    /// The header of the block.
+    pub header: Header,
+    /// The encoded transactions that were included in the block.
+    pub transactions: Vec<Bytes>,
+    /// The storage root of the `L2ToL1MessagePasser` contract after the block.
+    pub message_passer_storage_root: B256,
+}
+
+/// An [L2Executor] builds and executes L2 blocks from their [PayloadAttributes], as the execution
+/// engine of an OP Stack node does. The L2 state that execution reads is fetched from the host
+/// through the preimage oracle, with the [HintType::L2StateNode] and [HintType::L2Code] hints.
+///
+/// [HintType::L2StateNode]: kona_preimage::HintType::L2StateNode
+/// [HintType::L2Code]: kona_preimage::HintType::L2Code
+pub trait L2Executor {
+    /// Builds and executes the block of the given [PayloadAttributes] on top of its parent, with
+    /// the rules of the given rollup and L2 chain configs.
+    ///
+    /// # Returns
+    /// - `Ok(ExecutedBlock)` with the executed block.
+    /// - `Err(InvalidTransaction)` if a transaction of the attributes cannot be included.
+    /// - `Err(_)` if the block could not be built.
+    fn execute<O, H>(
+        &mut self,
+        oracle: &mut O,
+        hint_writer: &H,
+        rollup_config: &RollupConfig,
+        chain_config: &ChainConfig,
+        parent: &Header,
+        attributes: &PayloadAttributes,
+    ) -> Result<ExecutedBlock>
+    where
+        O: PreimageOracleClient,
+        H: HintWriterClient;
+}
+
+/// The error returned by an [L2Executor] for a transaction of the payload attributes that cannot be
+/// included in the block, e.g. because its nonce or signature is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InvalidTransaction {
+    /// The index of the transaction in the payload attributes.
+    pub(crate) index: usize,
+    /// The reason that the transaction cannot be included.
+    pub(crate) reason: String,
+}
+
+impl Display for InvalidTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Transaction {} is invalid: {}", self.index, self.reason)
+    }
+}
+
+/// The [UnavailableExecutor] is the [L2Executor] of builds without an L2 execution engine. It
+/// fails to execute any block, so only claims that need no new L2 blocks to be executed can be
+/// proven with it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnavailableExecutor;
+
+impl L2Executor for UnavailableExecutor {
+    fn execute<O, H>(
+        &mut self,
+        _: &mut O,
+        _: &H,
+        _: &RollupConfig,
+        _: &ChainConfig,
+        parent: &Header,
+        _: &PayloadAttributes,
+    ) -> Result<ExecutedBlock>
+    where
+        O: PreimageOracleClient,
+        H: HintWriterClient,
+    {
+        bail!("No L2 execution engine is available to execute L2 block {}", parent.number + 1)
+    }
+}
@@ -0,0 +1,313 @@
+//! Contains the [TrieState], the L2 state that the [StatelessExecutor] executes blocks on, and the
+//! [TrieDB], which exposes it to the EVM and fetches the parts of it that are used from the host.
+//!
+//! [StatelessExecutor]: super::StatelessExecutor
+
+use crate::mpt::{bytes_to_nibbles, TrieNode};
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_consensus::Header;
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_rlp::{Decodable, RlpDecodable, RlpEncodable};
+use anyhow::{anyhow, ensure, Error, Result};
+use core::fmt::{self, Debug};
+use kona_preimage::{
+    Hint, HintType, HintWriterClient, PreimageKey, PreimageKeyType, PreimageOracleClient,
+};
+use revm::{
+    primitives::{Account, AccountInfo, Bytecode, HashMap, KECCAK_EMPTY},
+    Database, DatabaseCommit,
+};
+
+/// An account as it is encoded in the state trie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+struct TrieAccount {
+    /// The nonce of the account.
+    nonce: u64,
+    /// The balance of the account.
+    balance: U256,
+    /// The root of the storage trie of the account.
+    storage_root: B256,
+    /// The hash of the code of the account.
+    code_hash: B256,
+}
+
+/// An account of the [TrieState] that was read or modified.
+#[derive(Debug, Clone, Default)]
+struct CachedAccount {
+    /// The account, without its code, or `None` if it does not exist.
+    info: Option<AccountInfo>,
+    /// The storage trie of the account.
+    storage: TrieNode,
+    /// Whether the account was modified since it was last written to the state trie.
+    dirty: bool,
+}
+
+impl CachedAccount {
+    /// Returns the [CachedAccount] of an account that was read from the state trie.
+    fn from_trie(account: Option<TrieAccount>) -> Self {
+        let Some(account) = account else { return Self::default() };
+        Self {
+            info: Some(AccountInfo {
+                balance: account.balance,
+                nonce: account.nonce,
+                code_hash: account.code_hash,
+                code: None,
+            }),
+            storage: TrieNode::new(account.storage_root),
+            dirty: false,
+        }
+    }
+}
+
+/// The L2 state that blocks are executed on. Its state trie starts out as the root of the state of
+/// the agreed upon L2 block, and the accounts, storage and code that execution uses are fetched
+/// from the host as they are first read.
+#[derive(Debug, Clone)]
+pub(crate) struct TrieState {
+    /// The root of the state trie, as of the last time it was computed.
+    root: B256,
+    /// The state trie.
+    trie: TrieNode,
+    /// The accounts that were read or modified, by address.
+    accounts: BTreeMap<Address, CachedAccount>,
+    /// The code that was read or deployed, by hash.
+    code: BTreeMap<B256, Bytes>,
+}
+
+impl TrieState {
+    /// Creates a [TrieState] with the given state root, which is fetched on first use.
+    pub(crate) fn new(root: B256) -> Self {
+        Self { root, trie: TrieNode::new(root), accounts: BTreeMap::new(), code: BTreeMap::new() }
+    }
+
+    /// Returns the root of the state trie, as of the last time it was computed.
+    pub(crate) fn root(&self) -> B256 {
+        self.root
+    }
+
+    /// Returns the cached account at `address`, reading it from the state trie on first use.
+    fn account<F>(&mut self, address: Address, fetch: &mut F) -> Result<&mut CachedAccount>
+    where
+        F: FnMut(B256) -> Result<Vec<u8>>,
+    {
+        // The account is looked up before it is inserted, as the trie may fail to fetch it.
+        if !self.accounts.contains_key(&address) {
+            let path = bytes_to_nibbles(keccak256(address).as_slice());
+            let account = self
+                .trie
+                .get(&path, fetch)?
+                .map(|encoded| TrieAccount::decode(&mut encoded.as_slice()))
+                .transpose()
+                .map_err(|e| anyhow!("Failed to decode account {address}: {e}"))?;
+            self.accounts.insert(address, CachedAccount::from_trie(account));
+        }
+        Ok(self.accounts.get_mut(&address).expect("The account was inserted above"))
+    }
+}
+
+/// The [TrieDB] is the EVM [Database] over a [TrieState], for the execution of a single block. The
+/// state trie nodes and code that are missing from the [TrieState] are fetched from the host with
+/// the [HintType::L2StateNode] and [HintType::L2Code] hints, and the hashes of the L2 blocks that
+/// execution reads with the [HintType::L2BlockHeader] hint.
+pub(crate) struct TrieDB<'a, O, H> {
+    /// The state that the block is executed on.
+    state: &'a mut TrieState,
+    /// The hashes and parent hashes of the known L2 blocks, by number.
+    block_hashes: &'a mut BTreeMap<u64, (B256, B256)>,
+    /// The changes that the EVM committed and that are not applied to the state yet.
+    pending: Vec<HashMap<Address, Account>>,
+    /// The preimage oracle.
+    oracle: &'a mut O,
+    /// The hint writer.
+    hint_writer: &'a H,
+}
+
+impl<'a, O, H> TrieDB<'a, O, H>
+where
+    O: PreimageOracleClient,
+    H: HintWriterClient,
+{
+    /// Creates a new [TrieDB] over `state`, which knows the hashes of the L2 blocks in
+    /// `block_hashes` and fetches the rest from the host.
+    pub(crate) fn new(
+        state: &'a mut TrieState,
+        block_hashes: &'a mut BTreeMap<u64, (B256, B256)>,
+        oracle: &'a mut O,
+        hint_writer: &'a H,
+    ) -> Self {
+        Self { state, block_hashes, pending: Vec::new(), oracle, hint_writer }
+    }
+
+    /// Applies the committed changes to the state, and computes the new state root.
+    pub(crate) fn state_root(&mut self) -> Result<B256> {
+        self.apply_pending()?;
+
+        let Self { state, oracle, hint_writer, .. } = self;
+        let mut fetch = |hash| fetch(&mut **oracle, *hint_writer, HintType::L2StateNode, hash);
+        for (address, account) in state.accounts.iter_mut().filter(|(_, account)| account.dirty) {
+            let path = bytes_to_nibbles(keccak256(address).as_slice());
+            match &account.info {
+                Some(info) => {
+                    let encoded = alloy_rlp::encode(TrieAccount {
+                        nonce: info.nonce,
+                        balance: info.balance,
+                        storage_root: account.storage.hash(),
+                        code_hash: info.code_hash,
+                    });
+                    state.trie.insert(&path, encoded, &mut fetch)?;
+                }
+                None => state.trie.delete(&path, &mut fetch)?,
+            }
+            account.dirty = false;
+        }
+
+        state.root = state.trie.hash();
+        Ok(state.root)
+    }
+
+    /// Applies the committed changes to the state, and returns the storage root of the account at
+    /// `address`.
+    pub(crate) fn storage_root(&mut self, address: Address) -> Result<B256> {
+        self.apply_pending()?;
+        Ok(self.account(address)?.storage.hash())
+    }
+
+    /// Returns the cached account at `address`, reading it from the state trie on first use.
+    fn account(&mut self, address: Address) -> Result<&mut CachedAccount> {
+        let Self { state, oracle, hint_writer, .. } = self;
+        state.account(address, &mut |hash| {
+            fetch(&mut **oracle, *hint_writer, HintType::L2StateNode, hash)
+        })
+    }
+
+    /// Applies the changes that the EVM committed to the accounts of the state, in order.
+    ///
+    /// The changes are applied lazily, as applying them may fetch trie nodes, which can fail,
+    /// while committing them cannot.
+    fn apply_pending(&mut self) -> Result<()> {
+        let Self { state, pending, oracle, hint_writer, .. } = self;
+        let mut fetch = |hash| fetch(&mut **oracle, *hint_writer, HintType::L2StateNode, hash);
+
+        for changes in pending.drain(..) {
+            for (address, account) in changes {
+                if !account.is_touched() {
+                    continue;
+                }
+                if let Some(code) = account.info.code.as_ref().filter(|code| !code.is_empty()) {
+                    state.code.insert(account.info.code_hash, code.original_bytes());
+                }
+
+                let cached = state.account(address, &mut fetch)?;
+                cached.dirty = true;
+                // Self-destructed accounts and touched empty accounts are removed, as of EIP-161.
+                if account.is_selfdestructed() || account.is_empty() {
+                    cached.info = None;
+                    cached.storage = TrieNode::Empty;
+                    continue;
+                }
+                if account.is_created() {
+                    cached.storage = TrieNode::Empty;
+                }
+
+                for (slot, value) in account.storage.iter().filter(|(_, v)| v.is_changed()) {
+                    let path = bytes_to_nibbles(keccak256(slot.to_be_bytes::<32>()).as_slice());
+                    let value = value.present_value();
+                    if value.is_zero() {
+                        cached.storage.delete(&path, &mut fetch)?;
+                    } else {
+                        cached.storage.insert(&path, alloy_rlp::encode(value), &mut fetch)?;
+                    }
+                }
+                cached.info = Some(AccountInfo { code: None, ..account.info });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<O, H> Database for TrieDB<'_, O, H>
+where
+    O: PreimageOracleClient,
+    H: HintWriterClient,
+{
+    type Error = Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>> {
+        self.apply_pending()?;
+        Ok(self.account(address)?.info.clone())
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::new());
+        }
+        if let Some(code) = self.state.code.get(&code_hash) {
+            return Ok(Bytecode::new_raw(code.clone()));
+        }
+
+        let code = Bytes::from(fetch(self.oracle, self.hint_writer, HintType::L2Code, code_hash)?);
+        self.state.code.insert(code_hash, code.clone());
+        Ok(Bytecode::new_raw(code))
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256> {
+        self.apply_pending()?;
+
+        let Self { state, oracle, hint_writer, .. } = self;
+        let mut fetch = |hash| fetch(&mut **oracle, *hint_writer, HintType::L2StateNode, hash);
+        let account = state.account(address, &mut fetch)?;
+        if account.info.is_none() {
+            return Ok(U256::ZERO);
+        }
+
+        let path = bytes_to_nibbles(keccak256(index.to_be_bytes::<32>()).as_slice());
+        account
+            .storage
+            .get(&path, &mut fetch)?
+            .map_or(Ok(U256::ZERO), |encoded| U256::decode(&mut encoded.as_slice()))
+            .map_err(|e| anyhow!("Failed to decode storage slot {index} of {address}: {e}"))
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256> {
+        let number = u64::try_from(number).map_err(|_| anyhow!("Invalid block number {number}"))?;
+        loop {
+            if let Some((hash, _)) = self.block_hashes.get(&number) {
+                return Ok(*hash);
+            }
+
+            let (&lowest, &(_, parent_hash)) = self
+                .block_hashes
+                .first_key_value()
+                .ok_or_else(|| anyhow!("No L2 block hashes are known"))?;
+            ensure!(number < lowest, "L2 block {number} is not known");
+
+            let encoded =
+                fetch(self.oracle, self.hint_writer, HintType::L2BlockHeader, parent_hash)?;
+            let header = Header::decode(&mut encoded.as_slice())
+                .map_err(|e| anyhow!("Failed to decode header: {e}"))?;
+            self.block_hashes.insert(lowest - 1, (parent_hash, header.parent_hash));
+        }
+    }
+}
+
+impl<O, H> DatabaseCommit for TrieDB<'_, O, H> {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        self.pending.push(changes);
+    }
+}
+
+impl<O, H> Debug for TrieDB<'_, O, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrieDB").field("state", &self.state).finish_non_exhaustive()
+    }
+}
+
+/// Sends a hint of the given type for `hash` to the host, and then fetches the preimage of `hash`.
+fn fetch<O, H>(oracle: &mut O, hint_writer: &H, hint_type: HintType, hash: B256) -> Result<Vec<u8>>
+where
+    O: PreimageOracleClient,
+    H: HintWriterClient,
+{
+    hint_writer.write_hint(&Hint::new(hint_type, hash.to_vec()))?;
+    oracle.get(PreimageKey::new(*hash, PreimageKeyType::Keccak256))
+}
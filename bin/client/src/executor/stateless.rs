@@ -0,0 +1,362 @@
+//! Contains the [StatelessExecutor], the [L2Executor] that executes blocks with `revm` on the L2
+//! state that it fetches from the host.
+
+use super::{
+    block::{next_base_fee, Receipt, EMPTY_OMMERS_HASH},
+    state::{TrieDB, TrieState},
+    ExecutedBlock, InvalidTransaction, L2Executor,
+};
+use crate::{
+    mpt::{ordered_trie_root, EMPTY_ROOT},
+    ChainConfig,
+};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_consensus::{Header, TxEnvelope};
+use alloy_eips::{eip2718::Decodable2718, eip2930::AccessList};
+use alloy_primitives::{address, Address, Bloom, Bytes, TxKind, B256, U256};
+use anyhow::{anyhow, bail, Error, Result};
+use kona_derive::{
+    traits::SignedRecoverable,
+    types::{PayloadAttributes, RawTransaction, RollupConfig, TxDeposit, BEACON_ROOTS_ADDRESS},
+};
+use kona_preimage::{HintWriterClient, PreimageOracleClient};
+use revm::{
+    primitives::{BlobExcessGasAndPrice, EVMError, ExecutionResult, OptimismFields, SpecId, TxEnv},
+    Database, DatabaseCommit, Evm,
+};
+
+/// The address of the `L2ToL1MessagePasser` predeploy, whose storage root the output root commits
+/// to.
+const L2_TO_L1_MESSAGE_PASSER: Address = address!("4200000000000000000000000000000000000016");
+
+/// The account that the EIP-4788 beacon block root is stored from at the start of a block.
+const SYSTEM_ADDRESS: Address = address!("fffffffffffffffffffffffffffffffffffffffe");
+
+/// The gas limit of the EIP-4788 beacon block root call.
+const BEACON_ROOT_CALL_GAS_LIMIT: u64 = 30_000_000;
+
+/// The [StatelessExecutor] is the [L2Executor] of the program. It executes blocks with `revm`, on
+/// the L2 state of their parent, which it fetches from the host as it is used.
+///
+/// The state that a block was executed on is kept, so that the next block executes on top of it
+/// without refetching it, and so that the state of executed blocks, which the host does not know,
+/// remains available.
+#[derive(Debug, Default)]
+pub struct StatelessExecutor {
+    /// The state of the last executed block, or of the first parent.
+    state: Option<TrieState>,
+    /// The hashes and parent hashes of the known L2 blocks, by number.
+    block_hashes: BTreeMap<u64, (B256, B256)>,
+}
+
+impl StatelessExecutor {
+    /// Creates a new [StatelessExecutor].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl L2Executor for StatelessExecutor {
+    fn execute<O, H>(
+        &mut self,
+        oracle: &mut O,
+        hint_writer: &H,
+        rollup_config: &RollupConfig,
+        chain_config: &ChainConfig,
+        parent: &Header,
+        attributes: &PayloadAttributes,
+    ) -> Result<ExecutedBlock>
+    where
+        O: PreimageOracleClient,
+        H: HintWriterClient,
+    {
+        let number = parent.number + 1;
+        let timestamp = attributes.timestamp;
+        let Some(optimism) = chain_config.optimism else {
+            bail!("The L2 chain config has no OP Stack parameters");
+        };
+        let Some(gas_limit) = attributes.gas_limit else {
+            bail!("The payload attributes of L2 block {number} have no gas limit");
+        };
+        if rollup_config.canyon_time == Some(timestamp) {
+            bail!("Executing the Canyon activation block, which deploys the create2 deployer, is not supported");
+        }
+
+        let is_canyon = rollup_config.is_canyon_active(timestamp);
+        let is_ecotone = rollup_config.is_ecotone_active(timestamp);
+        let denominator = if is_canyon {
+            optimism.eip1559_denominator_canyon.unwrap_or(optimism.eip1559_denominator)
+        } else {
+            optimism.eip1559_denominator
+        };
+        let base_fee = next_base_fee(parent, optimism.eip1559_elasticity, denominator)?;
+
+        // The block is executed on a copy of the state, so that the state is left untouched if
+        // the block turns out to be invalid.
+        let mut state = match &self.state {
+            Some(state) if state.root() == parent.state_root => state.clone(),
+            _ => TrieState::new(parent.state_root),
+        };
+        let parent_hash = parent.hash_slow();
+        self.block_hashes.insert(parent.number, (parent_hash, parent.parent_hash));
+
+        let db = TrieDB::new(&mut state, &mut self.block_hashes, oracle, hint_writer);
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .optimism()
+            .with_spec_id(spec_id(rollup_config, timestamp))
+            .modify_cfg_env(|cfg| cfg.chain_id = rollup_config.l2_chain_id)
+            .modify_block_env(|block| {
+                block.number = U256::from(number);
+                block.coinbase = attributes.fee_recipient;
+                block.timestamp = U256::from(timestamp);
+                block.gas_limit = U256::from(gas_limit);
+                block.basefee = U256::from(base_fee);
+                block.difficulty = U256::ZERO;
+                block.prevrandao = Some(attributes.prev_randao);
+                block.blob_excess_gas_and_price = is_ecotone.then(|| BlobExcessGasAndPrice::new(0));
+            })
+            .build();
+
+        let parent_beacon_block_root =
+            if is_ecotone { attributes.parent_beacon_block_root } else { None };
+        if is_ecotone {
+            let root = parent_beacon_block_root.ok_or_else(|| {
+                anyhow!("Ecotone L2 block {number} has no parent beacon block root")
+            })?;
+            apply_beacon_root_call(&mut evm, root)?;
+        }
+
+        let mut receipts = Vec::with_capacity(attributes.transactions.len());
+        let mut cumulative_gas_used = 0u64;
+        for (index, transaction) in attributes.transactions.iter().enumerate() {
+            let invalid = |reason: String| Error::msg(InvalidTransaction { index, reason });
+            let (tx_env, tx_type) = tx_env(transaction).map_err(|e| invalid(e.to_string()))?;
+            if cumulative_gas_used + tx_env.gas_limit > gas_limit {
+                return Err(invalid("The block gas limit is exceeded".to_string()));
+            }
+
+            let is_deposit = tx_env.optimism.source_hash.is_some();
+            let deposit_nonce = if is_deposit && rollup_config.is_regolith_active(timestamp) {
+                Some(evm.db_mut().basic(tx_env.caller)?.map_or(0, |account| account.nonce))
+            } else {
+                None
+            };
+
+            *evm.tx_mut() = tx_env;
+            let result = match evm.transact_commit() {
+                Ok(result) => result,
+                Err(EVMError::Transaction(e)) => return Err(invalid(e.to_string())),
+                Err(EVMError::Database(e)) => return Err(e),
+                Err(e) => bail!("Failed to execute transaction {index} of L2 block {number}: {e}"),
+            };
+
+            cumulative_gas_used += result.gas_used();
+            let success = result.is_success();
+            let logs = match result {
+                ExecutionResult::Success { logs, .. } => logs,
+                _ => Vec::new(),
+            };
+            receipts.push(Receipt {
+                tx_type,
+                success,
+                cumulative_gas_used,
+                logs,
+                deposit_nonce,
+                deposit_receipt_version: (is_deposit && is_canyon).then_some(1),
+            });
+        }
+
+        let db = evm.db_mut();
+        let state_root = db.state_root()?;
+        let message_passer_storage_root = db.storage_root(L2_TO_L1_MESSAGE_PASSER)?;
+        drop(evm);
+
+        let transactions =
+            attributes.transactions.iter().map(|tx| tx.0.clone()).collect::<Vec<Bytes>>();
+        let encoded_receipts = receipts.iter().map(Receipt::encode_2718).collect::<Vec<_>>();
+        let mut logs_bloom = Bloom::ZERO;
+        receipts
+            .iter()
+            .flat_map(|receipt| &receipt.logs)
+            .for_each(|log| logs_bloom.accrue_log(log));
+
+        let header = Header {
+            parent_hash,
+            ommers_hash: EMPTY_OMMERS_HASH,
+            beneficiary: attributes.fee_recipient,
+            state_root,
+            transactions_root: ordered_trie_root(&transactions)?,
+            receipts_root: ordered_trie_root(&encoded_receipts)?,
+            withdrawals_root: is_canyon.then_some(EMPTY_ROOT),
+            logs_bloom,
+            difficulty: U256::ZERO,
+            number,
+            gas_limit,
+            gas_used: cumulative_gas_used,
+            timestamp,
+            extra_data: Bytes::new(),
+            mix_hash: attributes.prev_randao,
+            base_fee_per_gas: Some(base_fee),
+            blob_gas_used: is_ecotone.then_some(0),
+            excess_blob_gas: is_ecotone.then_some(0),
+            parent_beacon_block_root,
+            ..Default::default()
+        };
+
+        self.state = Some(state);
+        Ok(ExecutedBlock { header, transactions, message_passer_storage_root })
+    }
+}
+
+/// Returns the EVM specification of the L2 block with the given timestamp.
+fn spec_id(rollup_config: &RollupConfig, timestamp: u64) -> SpecId {
+    if rollup_config.is_fjord_active(timestamp) {
+        SpecId::FJORD
+    } else if rollup_config.is_ecotone_active(timestamp) {
+        SpecId::ECOTONE
+    } else if rollup_config.is_canyon_active(timestamp) {
+        SpecId::CANYON
+    } else if rollup_config.is_regolith_active(timestamp) {
+        SpecId::REGOLITH
+    } else {
+        SpecId::BEDROCK
+    }
+}
+
+/// Decodes a transaction of the payload attributes into the [TxEnv] it is executed with, and its
+/// EIP-2718 type. The sender of a transaction that is not a deposit is recovered from its
+/// signature.
+fn tx_env(transaction: &RawTransaction) -> Result<(TxEnv, u8)> {
+    let enveloped_tx = Some(transaction.0.clone());
+    if transaction.is_deposit() {
+        let deposit = TxDeposit::decode_2718(&mut transaction.0.as_ref())
+            .map_err(|e| anyhow!("Failed to decode deposit: {e}"))?;
+        let mint = u128::try_from(deposit.mint)
+            .map_err(|_| anyhow!("Deposit mint {} overflows", deposit.mint))?;
+        let tx_env = TxEnv {
+            caller: deposit.from,
+            gas_limit: deposit.gas_limit,
+            gas_price: U256::ZERO,
+            transact_to: deposit.to,
+            value: deposit.value,
+            data: deposit.input,
+            optimism: OptimismFields {
+                source_hash: Some(deposit.source_hash),
+                mint: Some(mint),
+                is_system_transaction: Some(deposit.is_system_transaction),
+                enveloped_tx,
+            },
+            ..Default::default()
+        };
+        return Ok((tx_env, transaction.0[0]));
+    }
+
+    let envelope = TxEnvelope::decode_2718(&mut transaction.0.as_ref())
+        .map_err(|e| anyhow!("Failed to decode transaction: {e}"))?;
+    let caller = envelope.recover_public_key()?;
+    let optimism =
+        OptimismFields { is_system_transaction: Some(false), enveloped_tx, ..Default::default() };
+    let tx_env = match &envelope {
+        TxEnvelope::Legacy(tx) => {
+            let tx = tx.tx();
+            TxEnv {
+                caller,
+                gas_limit: tx.gas_limit,
+                gas_price: U256::from(tx.gas_price),
+                transact_to: tx.to,
+                value: tx.value,
+                data: tx.input.clone(),
+                nonce: Some(tx.nonce),
+                chain_id: tx.chain_id,
+                optimism,
+                ..Default::default()
+            }
+        }
+        TxEnvelope::Eip2930(tx) => {
+            let tx = tx.tx();
+            TxEnv {
+                caller,
+                gas_limit: tx.gas_limit,
+                gas_price: U256::from(tx.gas_price),
+                transact_to: tx.to,
+                value: tx.value,
+                data: tx.input.clone(),
+                nonce: Some(tx.nonce),
+                chain_id: Some(tx.chain_id),
+                access_list: access_list(&tx.access_list),
+                optimism,
+                ..Default::default()
+            }
+        }
+        TxEnvelope::Eip1559(tx) => {
+            let tx = tx.tx();
+            TxEnv {
+                caller,
+                gas_limit: tx.gas_limit,
+                gas_price: U256::from(tx.max_fee_per_gas),
+                gas_priority_fee: Some(U256::from(tx.max_priority_fee_per_gas)),
+                transact_to: tx.to,
+                value: tx.value,
+                data: tx.input.clone(),
+                nonce: Some(tx.nonce),
+                chain_id: Some(tx.chain_id),
+                access_list: access_list(&tx.access_list),
+                optimism,
+                ..Default::default()
+            }
+        }
+        _ => bail!("Transactions of type {} are not allowed on L2", transaction.0[0]),
+    };
+    let tx_type = if matches!(envelope, TxEnvelope::Legacy(_)) { 0 } else { transaction.0[0] };
+    Ok((tx_env, tx_type))
+}
+
+/// Converts an EIP-2930 access list into the access list of a [TxEnv].
+fn access_list(access_list: &AccessList) -> Vec<(Address, Vec<U256>)> {
+    access_list
+        .0
+        .iter()
+        .map(|item| {
+            let keys = item.storage_keys.iter().map(|key| U256::from_be_bytes(key.0)).collect();
+            (item.address, keys)
+        })
+        .collect()
+}
+
+/// Stores the parent beacon block root in the EIP-4788 beacon block roots contract, with a call
+/// from the [SYSTEM_ADDRESS] at the start of the block.
+///
+/// The call is executed as a deposit, which pays no fees, and only its changes to the beacon
+/// block roots contract are kept.
+fn apply_beacon_root_call<DB>(evm: &mut Evm<'_, (), DB>, root: B256) -> Result<()>
+where
+    DB: Database<Error = Error> + DatabaseCommit,
+{
+    *evm.tx_mut() = TxEnv {
+        caller: SYSTEM_ADDRESS,
+        gas_limit: BEACON_ROOT_CALL_GAS_LIMIT,
+        gas_price: U256::ZERO,
+        transact_to: TxKind::Call(BEACON_ROOTS_ADDRESS),
+        data: root.into(),
+        optimism: OptimismFields {
+            source_hash: Some(B256::ZERO),
+            mint: None,
+            is_system_transaction: Some(false),
+            enveloped_tx: Some(Bytes::new()),
+        },
+        ..Default::default()
+    };
+    let mut state = match evm.transact() {
+        Ok(result) => result.state,
+        Err(EVMError::Database(e)) => return Err(e),
+        Err(e) => bail!("Failed to store the parent beacon block root: {e}"),
+    };
+    state.retain(|address, _| *address == BEACON_ROOTS_ADDRESS);
+    evm.db_mut().commit(state);
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Contains the providers of L1 chain data and blobs to the derivation pipeline, which fetch them
+//! from the host through the preimage oracle.
+
+use crate::{mpt::ordered_trie_values, oracle::OracleHandle};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
+use alloy_consensus::{Header, Receipt, ReceiptWithBloom, TxEnvelope};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_primitives::B256;
+use alloy_rlp::Decodable;
+use anyhow::{anyhow, ensure, Result};
+use async_trait::async_trait;
+use core::fmt::{self, Debug, Display};
+use kona_derive::{
+    traits::{BlobProvider, ChainProvider},
+    types::{Blob, BlockInfo, IndexedBlobHash},
+};
+use kona_preimage::{
+    get_blob, Hint, HintType, HintWriterClient, PreimageKey, PreimageKeyType, PreimageOracleClient,
+};
+use spin::Mutex;
+
+/// The error returned for L1 blocks past the L1 head, which the program may not derive from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PastL1Head {
+    /// The number of the requested block.
+    pub(crate) number: u64,
+}
+
+impl Display for PastL1Head {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "L1 block {} is past the L1 head", self.number)
+    }
+}
+
+/// The [OracleL1ChainProvider] is a [ChainProvider] for the L1 chain up to the L1 head of the
+/// program. Blocks are looked up by number by walking back from the L1 head, and the hashes that
+/// were walked past are remembered.
+pub(crate) struct OracleL1ChainProvider<O, H> {
+    /// The hash of the L1 head.
+    l1_head: B256,
+    /// The oracle to fetch the L1 chain data from.
+    oracle: OracleHandle<O, H>,
+    /// The hashes of the L1 blocks that were walked past, by number.
+    hashes: Arc<Mutex<BTreeMap<u64, B256>>>,
+}
+
+impl<O, H> OracleL1ChainProvider<O, H>
+where
+    O: PreimageOracleClient,
+    H: HintWriterClient,
+{
+    /// Creates a new [OracleL1ChainProvider] for the L1 chain up to the given L1 head.
+    pub(crate) fn new(l1_head: B256, oracle: OracleHandle<O, H>) -> Self {
+        Self { l1_head, oracle, hashes: Arc::new(Mutex::new(BTreeMap::new())) }
+    }
+
+    /// Fetches the [Header] of the L1 block with the given hash.
+    fn header(&self, hash: B256) -> Result<Header> {
+        let encoded = self.oracle.get(
+            &Hint::new(HintType::L1BlockHeader, hash.to_vec()),
+            PreimageKey::new(*hash, PreimageKeyType::Keccak256),
+        )?;
+        Header::decode(&mut encoded.as_slice())
+            .map_err(|e| anyhow!("Failed to decode L1 header {hash}: {e}"))
+    }
+
+    /// Returns the hash of the L1 block with the given number.
+    ///
+    /// # Returns
+    /// - `Ok(B256)` with the block hash.
+    /// - `Err(PastL1Head)` if the block is past the L1 head.
+    /// - `Err(_)` if a header could not be fetched.
+    fn hash_by_number(&self, number: u64) -> Result<B256> {
+        let mut hashes = self.hashes.lock();
+        if hashes.is_empty() {
+            let head = self.header(self.l1_head)?;
+            hashes.insert(head.number, self.l1_head);
+        }
+        if let Some(hash) = hashes.get(&number) {
+            return Ok(*hash);
+        }
+
+        let (&lowest, &lowest_hash) = hashes.first_key_value().expect("The L1 head is known");
+        if number > lowest {
+            return Err(anyhow::Error::msg(PastL1Head { number }));
+        }
+        let mut parent_hash = self.header(lowest_hash)?.parent_hash;
+        for parent in (number..lowest).rev() {
+            hashes.insert(parent, parent_hash);
+            if parent > number {
+                parent_hash = self.header(parent_hash)?.parent_hash;
+            }
+        }
+        Ok(parent_hash)
+    }
+}
+
+impl<O, H> Debug for OracleL1ChainProvider<O, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OracleL1ChainProvider").field("l1_head", &self.l1_head).finish()
+    }
+}
+
+impl<O, H> Clone for OracleL1ChainProvider<O, H> {
+    fn clone(&self) -> Self {
+        Self { l1_head: self.l1_head, oracle: self.oracle.clone(), hashes: self.hashes.clone() }
+    }
+}
+
+#[async_trait]
+impl<O, H> ChainProvider for OracleL1ChainProvider<O, H>
+where
+    O: PreimageOracleClient + Send,
+    H: HintWriterClient + Send + Sync,
+{
+    async fn header_by_hash(&self, hash: B256) -> Result<Header> {
+        self.header(hash)
+    }
+
+    async fn block_info_by_number(&self, number: u64) -> Result<BlockInfo> {
+        let hash = self.hash_by_number(number)?;
+        let header = self.header(hash)?;
+        Ok(BlockInfo::new(hash, header.number, header.parent_hash, header.timestamp))
+    }
+
+    async fn receipts_by_hash(&self, hash: B256) -> Result<Vec<Receipt>> {
+        let header = self.header(hash)?;
+        let hint = Hint::new(HintType::L1Receipts, hash.to_vec());
+        let encoded = self.oracle.with_oracle(&hint, |oracle| {
+            ordered_trie_values(header.receipts_root, |node| {
+                oracle.get(PreimageKey::new(*node, PreimageKeyType::Keccak256))
+            })
+        })?;
+
+        encoded
+            .iter()
+            .map(|receipt| {
+                // Typed receipts are prefixed with their type, which is below the RLP list prefix.
+                let mut buf = receipt.as_slice();
+                if buf.first().is_some_and(|ty| *ty < 0x7f) {
+                    buf = &buf[1..];
+                }
+                ReceiptWithBloom::decode(&mut buf)
+                    .map(|receipt| receipt.receipt)
+                    .map_err(|e| anyhow!("Failed to decode receipt of L1 block {hash}: {e}"))
+            })
+            .collect()
+    }
+
+    async fn block_info_and_transactions_by_hash(
+        &self,
+        hash: B256,
+    ) -> Result<(BlockInfo, Vec<TxEnvelope>)> {
+        let header = self.header(hash)?;
+        let hint = Hint::new(HintType::L1Transactions, hash.to_vec());
+        let encoded = self.oracle.with_oracle(&hint, |oracle| {
+            ordered_trie_values(header.transactions_root, |node| {
+                oracle.get(PreimageKey::new(*node, PreimageKeyType::Keccak256))
+            })
+        })?;
+
+        let transactions = encoded
+            .iter()
+            .map(|tx| {
+                TxEnvelope::decode_2718(&mut tx.as_slice())
+                    .map_err(|e| anyhow!("Failed to decode transaction of L1 block {hash}: {e}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let block_info = BlockInfo::new(hash, header.number, header.parent_hash, header.timestamp);
+        Ok((block_info, transactions))
+    }
+}
+
+/// The [OracleBlobProvider] is a [BlobProvider] that fetches blobs field element by field element
+/// from the host, by the KZG commitments that their versioned hashes commit to.
+pub(crate) struct OracleBlobProvider<O, H> {
+    /// The oracle to fetch the blobs from.
+    oracle: OracleHandle<O, H>,
+}
+
+impl<O, H> OracleBlobProvider<O, H> {
+    /// Creates a new [OracleBlobProvider].
+    pub(crate) fn new(oracle: OracleHandle<O, H>) -> Self {
+        Self { oracle }
+    }
+}
+
+impl<O, H> Debug for OracleBlobProvider<O, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OracleBlobProvider").finish_non_exhaustive()
+    }
+}
+
+impl<O, H> Clone for OracleBlobProvider<O, H> {
+    fn clone(&self) -> Self {
+        Self { oracle: self.oracle.clone() }
+    }
+}
+
+#[async_trait]
+impl<O, H> BlobProvider for OracleBlobProvider<O, H>
+where
+    O: PreimageOracleClient + Send,
+    H: HintWriterClient + Send + Sync,
+{
+    async fn get_blobs(
+        &self,
+        block_ref: &BlockInfo,
+        blob_hashes: Vec<IndexedBlobHash>,
+    ) -> Result<Vec<Blob>> {
+        blob_hashes
+            .iter()
+            .map(|blob_hash| {
+                let mut data = Vec::with_capacity(48);
+                data.extend_from_slice(blob_hash.hash.as_slice());
+                data.extend_from_slice(&(blob_hash.index as u64).to_be_bytes());
+                data.extend_from_slice(&block_ref.timestamp.to_be_bytes());
+
+                self.oracle.with_oracle(&Hint::new(HintType::L1Blob, data), |oracle| {
+                    let commitment =
+                        oracle.get(PreimageKey::new(*blob_hash.hash, PreimageKeyType::Sha256))?;
+                    ensure!(
+                        commitment.len() == 48,
+                        "Invalid KZG commitment length {} for blob {}",
+                        commitment.len(),
+                        blob_hash.hash
+                    );
+                    let commitment: [u8; 48] = commitment.try_into().expect("Length is checked");
+                    get_blob(oracle, &commitment).map(|blob| Blob::from(*blob))
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,238 @@
+//! Contains helpers for fetching L2 chain data from the host through the preimage oracle, and the
+//! provider of the safe L2 chain to the derivation pipeline.
+
+use crate::{mpt::ordered_trie_values, oracle::OracleHandle, output_root::OutputRoot};
+use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
+use alloy_consensus::Header;
+use alloy_primitives::{Bytes, B256, U256};
+use alloy_rlp::Decodable;
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use core::fmt::{self, Debug};
+use kona_derive::{
+    traits::SafeBlockFetcher,
+    types::{
+        ExecutionPayload, ExecutionPayloadEnvelope, L1BlockInfoTx, L2BlockInfo, RollupConfig,
+        SystemConfig, TxDeposit, Withdrawals,
+    },
+};
+use kona_preimage::{
+    Hint, HintType, HintWriterClient, PreimageKey, PreimageKeyType, PreimageOracleClient,
+};
+use spin::Mutex;
+
+/// Fetches the preimage of the given output root.
+pub(crate) fn output_root_by_hash<O, H>(
+    oracle: &OracleHandle<O, H>,
+    output_root: B256,
+) -> Result<OutputRoot>
+where
+    O: PreimageOracleClient,
+    H: HintWriterClient,
+{
+    let preimage = oracle.get(
+        &Hint::new(HintType::L2Output, output_root.to_vec()),
+        PreimageKey::new(*output_root, PreimageKeyType::Keccak256),
+    )?;
+    OutputRoot::decode(&preimage)
+}
+
+/// Fetches the [Header] of the L2 block with the given hash.
+pub(crate) fn header_by_hash<O, H>(oracle: &OracleHandle<O, H>, hash: B256) -> Result<Header>
+where
+    O: PreimageOracleClient,
+    H: HintWriterClient,
+{
+    let encoded = oracle.get(
+        &Hint::new(HintType::L2BlockHeader, hash.to_vec()),
+        PreimageKey::new(*hash, PreimageKeyType::Keccak256),
+    )?;
+    Header::decode(&mut encoded.as_slice()).map_err(|e| anyhow!("Failed to decode header: {e}"))
+}
+
+/// The [OracleL2ChainProvider] is a [SafeBlockFetcher] for the L2 chain up to the agreed upon L2
+/// block, which it fetches from the host, and the blocks that the program executed on top of it.
+pub(crate) struct OracleL2ChainProvider<O, H> {
+    /// The rollup config.
+    rollup_config: Arc<RollupConfig>,
+    /// The oracle to fetch the L2 chain data from.
+    oracle: OracleHandle<O, H>,
+    /// The blocks that were fetched or executed so far.
+    chain: Arc<Mutex<L2Chain>>,
+}
+
+/// The blocks of the L2 chain that the [OracleL2ChainProvider] knows about, by number.
+#[derive(Debug, Default)]
+struct L2Chain {
+    /// The hashes and headers of the known blocks.
+    headers: BTreeMap<u64, (B256, Header)>,
+    /// The payloads of the known blocks, which are assembled on first use.
+    payloads: BTreeMap<u64, ExecutionPayloadEnvelope>,
+}
+
+impl<O, H> OracleL2ChainProvider<O, H>
+where
+    O: PreimageOracleClient,
+    H: HintWriterClient,
+{
+    /// Creates a new [OracleL2ChainProvider] for the L2 chain up to the given agreed upon block.
+    pub(crate) fn new(
+        rollup_config: Arc<RollupConfig>,
+        oracle: OracleHandle<O, H>,
+        agreed_hash: B256,
+        agreed_header: Header,
+    ) -> Self {
+        let mut chain = L2Chain::default();
+        chain.headers.insert(agreed_header.number, (agreed_hash, agreed_header));
+        Self { rollup_config, oracle, chain: Arc::new(Mutex::new(chain)) }
+    }
+
+    /// Returns the hash and [Header] of the L2 block with the given number. Blocks before the
+    /// agreed upon block are found by walking back from the lowest known block.
+    pub(crate) fn header_by_number(&self, number: u64) -> Result<(B256, Header)> {
+        let mut chain = self.chain.lock();
+        if let Some(known) = chain.headers.get(&number) {
+            return Ok(known.clone());
+        }
+
+        let (&lowest, (_, lowest_header)) =
+            chain.headers.first_key_value().expect("The agreed upon block is known");
+        if number > lowest {
+            bail!("L2 block {number} is neither executed nor before the agreed upon block");
+        }
+        let mut hash = lowest_header.parent_hash;
+        for _ in number..lowest {
+            let header = header_by_hash(&self.oracle, hash)?;
+            let parent_hash = header.parent_hash;
+            chain.headers.insert(header.number, (hash, header));
+            hash = parent_hash;
+        }
+        chain.headers.get(&number).cloned().ok_or_else(|| anyhow!("L2 block {number} not found"))
+    }
+
+    /// Inserts a block that was executed on top of the known chain, along with its transactions.
+    pub(crate) fn insert_executed(&self, header: Header, transactions: Vec<Bytes>) {
+        let hash = header.hash_slow();
+        let payload = payload_from_header(hash, &header, transactions);
+        let mut chain = self.chain.lock();
+        chain.payloads.insert(header.number, payload);
+        chain.headers.insert(header.number, (hash, header));
+    }
+
+    /// Returns the [ExecutionPayloadEnvelope] of the L2 block with the given number.
+    fn payload(&self, number: u64) -> Result<ExecutionPayloadEnvelope> {
+        if let Some(payload) = self.chain.lock().payloads.get(&number) {
+            return Ok(payload.clone());
+        }
+
+        let (hash, header) = self.header_by_number(number)?;
+        let hint = Hint::new(HintType::L2Transactions, hash.to_vec());
+        let transactions = self.oracle.with_oracle(&hint, |oracle| {
+            ordered_trie_values(header.transactions_root, |node| {
+                oracle.get(PreimageKey::new(*node, PreimageKeyType::Keccak256))
+            })
+        })?;
+
+        let payload =
+            payload_from_header(hash, &header, transactions.into_iter().map(Bytes::from).collect());
+        self.chain.lock().payloads.insert(number, payload.clone());
+        Ok(payload)
+    }
+
+    /// Returns the [SystemConfig] of the L2 block with the given number, as recorded in its L1
+    /// info deposit and gas limit.
+    pub(crate) fn system_config_by_number(&self, number: u64) -> Result<SystemConfig> {
+        if number == self.rollup_config.genesis.l2.number {
+            return Ok(self.rollup_config.genesis.system_config);
+        }
+
+        let payload = self.payload(number)?.execution_payload;
+        let Some(first_tx) = payload.transactions.first() else {
+            bail!("L2 block {number} is missing the L1 info deposit");
+        };
+        let deposit = TxDeposit::decode_2718(&mut first_tx.as_ref())
+            .map_err(|e| anyhow!("First transaction of L2 block {number} is not a deposit: {e}"))?;
+
+        let l1_info = L1BlockInfoTx::decode_calldata(&deposit.input)?;
+        let (l1_fee_overhead, l1_fee_scalar) = match &l1_info {
+            L1BlockInfoTx::Bedrock(info) => (info.l1_fee_overhead, info.l1_fee_scalar),
+            L1BlockInfoTx::Ecotone(info) => {
+                // The version 1 scalar packs both Ecotone scalars, and has no overhead.
+                let mut scalar = [0u8; 32];
+                scalar[0] = 1;
+                scalar[24..28].copy_from_slice(&info.blob_base_fee_scalar.to_be_bytes());
+                scalar[28..32].copy_from_slice(&info.base_fee_scalar.to_be_bytes());
+                (U256::ZERO, U256::from_be_bytes(scalar))
+            }
+        };
+
+        Ok(SystemConfig {
+            batcher_addr: l1_info.batcher_address(),
+            gas_limit: U256::from(payload.gas_limit),
+            l1_fee_overhead,
+            l1_fee_scalar,
+            // The unsafe block signer is not recorded in L2 blocks, and derivation does not use it.
+            unsafe_block_signer: Default::default(),
+        })
+    }
+}
+
+impl<O, H> Debug for OracleL2ChainProvider<O, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OracleL2ChainProvider").finish_non_exhaustive()
+    }
+}
+
+impl<O, H> Clone for OracleL2ChainProvider<O, H> {
+    fn clone(&self) -> Self {
+        Self {
+            rollup_config: self.rollup_config.clone(),
+            oracle: self.oracle.clone(),
+            chain: self.chain.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl<O, H> SafeBlockFetcher for OracleL2ChainProvider<O, H>
+where
+    O: PreimageOracleClient + Send,
+    H: HintWriterClient + Send + Sync,
+{
+    async fn l2_block_info_by_number(&self, number: u64) -> Result<L2BlockInfo> {
+        self.payload(number)?.execution_payload.to_l2_block_info(&self.rollup_config.genesis)
+    }
+
+    async fn payload_by_number(&self, number: u64) -> Result<ExecutionPayloadEnvelope> {
+        self.payload(number)
+    }
+}
+
+/// Assembles the [ExecutionPayloadEnvelope] of an L2 block from its header and transactions.
+fn payload_from_header(
+    hash: B256,
+    header: &Header,
+    transactions: Vec<Bytes>,
+) -> ExecutionPayloadEnvelope {
+    ExecutionPayloadEnvelope {
+        parent_beacon_block_root: header.parent_beacon_block_root,
+        execution_payload: ExecutionPayload {
+            parent_hash: header.parent_hash,
+            fee_recipient: header.beneficiary,
+            state_root: header.state_root,
+            receipts_root: header.receipts_root,
+            prev_randao: header.mix_hash,
+            block_number: header.number,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: header.timestamp,
+            base_fee_per_gas: U256::from(header.base_fee_per_gas.unwrap_or_default()),
+            block_hash: hash,
+            transactions,
+            withdrawals: header.withdrawals_root.map(|_| Withdrawals {}),
+            blob_gas_used: header.blob_gas_used,
+            excess_blob_gas: header.excess_blob_gas,
+            ..Default::default()
+        },
+    }
+}
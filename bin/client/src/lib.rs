@@ -0,0 +1,555 @@
+//! The OP Stack fault proof program. It loads its [BootInfo] from the host, derives and executes
+//! the L2 chain from the agreed upon output root up to the disputed block, and checks the disputed
+//! output root claim against the output root it computed.
+//!
+//! The program is generic over its [PreimageOracleClient] and [HintWriterClient], so that it can
+//! be run on top of a Fault Proof VM, or natively by the host.
+
+#![warn(missing_debug_implementations, missing_docs, unreachable_pub, rustdoc::all)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{sync::Arc, vec::Vec};
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail, Result};
+use kona_common::block_on;
+use kona_derive::{
+    sources::DataSourceFactory,
+    stages::StatefulAttributesBuilder,
+    traits::{ChainProvider, SafeBlockFetcher},
+    types::{PayloadAttributes, RollupConfig, StageError},
+    PipelineBuilder,
+};
+use kona_preimage::{BootInfo, HintWriterClient, PreimageOracleClient};
+
+mod chain_config;
+pub use chain_config::{ChainConfig, OptimismConfig};
+
+mod executor;
+use executor::InvalidTransaction;
+pub use executor::{ExecutedBlock, L2Executor, StatelessExecutor, UnavailableExecutor};
+
+mod l1;
+use l1::{OracleBlobProvider, OracleL1ChainProvider, PastL1Head};
+
+mod l2;
+use l2::OracleL2ChainProvider;
+
+mod mpt;
+
+mod oracle;
+use oracle::OracleHandle;
+
+mod output_root;
+use output_root::OutputRoot;
+
+mod telemetry;
+use telemetry::ClientTelemetry;
+
+/// Checks the disputed output root claim, executing the derived L2 blocks with `executor`.
+///
+/// The L2 chain is derived from the L1 chain up to the L1 head. If the L1 chain is exhausted
+/// before the claimed block is derived, the claim is checked against the output root of the last
+/// derived block.
+///
+/// # Returns
+/// - `Ok(true)` if the claim matches the computed output root.
+/// - `Ok(false)` if the claim does not match the computed output root.
+/// - `Err(_)` if the output root could not be computed.
+pub fn check_claim<O, H, E>(mut oracle: O, hint_writer: H, mut executor: E) -> Result<bool>
+where
+    O: PreimageOracleClient + Send,
+    H: HintWriterClient + Send + Sync,
+    E: L2Executor,
+{
+    let boot_info = BootInfo::load(&mut oracle)?;
+    let rollup_config: RollupConfig = serde_json::from_slice(&boot_info.rollup_config)
+        .map_err(|e| anyhow!("Failed to parse rollup config: {e}"))?;
+    if rollup_config.l2_chain_id != boot_info.l2_chain_id {
+        bail!(
+            "Rollup config chain ID {} does not match L2 chain ID {}",
+            rollup_config.l2_chain_id,
+            boot_info.l2_chain_id
+        );
+    }
+    let chain_config: ChainConfig = serde_json::from_slice(&boot_info.l2_chain_config)
+        .map_err(|e| anyhow!("Failed to parse L2 chain config: {e}"))?;
+    let oracle = OracleHandle::new(oracle, hint_writer);
+
+    // Load the L2 block that the agreed upon output root commits to.
+    let agreed_output_root = B256::from(boot_info.l2_output_root);
+    let agreed_output = l2::output_root_by_hash(&oracle, agreed_output_root)?;
+    if agreed_output.hash() != agreed_output_root {
+        bail!("Agreed output root preimage does not hash to {agreed_output_root}");
+    }
+    let agreed_header = l2::header_by_hash(&oracle, agreed_output.block_hash)?;
+    let agreed_number = agreed_header.number;
+
+    let claimed_output_root = B256::from(boot_info.l2_claim);
+    let claimed_number = boot_info.l2_claim_block_number;
+    if claimed_number < agreed_number {
+        bail!("Claimed block {claimed_number} is before the agreed upon block {agreed_number}");
+    } else if claimed_number == agreed_number {
+        // No blocks need to be derived, so the claim must be the agreed upon output root.
+        return Ok(claimed_output_root == agreed_output_root);
+    }
+
+    let rollup_config = Arc::new(rollup_config);
+    let l1_provider = OracleL1ChainProvider::new(B256::from(boot_info.l1_head), oracle.clone());
+    let l2_provider = OracleL2ChainProvider::new(
+        rollup_config.clone(),
+        oracle.clone(),
+        agreed_output.block_hash,
+        agreed_header,
+    );
+    let mut safe_head = block_on(l2_provider.l2_block_info_by_number(agreed_number))?;
+    let system_config = l2_provider.system_config_by_number(agreed_number)?;
+
+    let mut pipeline = PipelineBuilder::new()
+        .rollup_config(rollup_config.clone())
+        .chain_provider(l1_provider.clone())
+        .l2_block_fetcher(l2_provider.clone())
+        .dap_source(DataSourceFactory::new(
+            l1_provider.clone(),
+            OracleBlobProvider::new(oracle.clone()),
+            *rollup_config,
+        ))
+        .attributes_builder(StatefulAttributesBuilder::new(
+            rollup_config.clone(),
+            system_config,
+            l1_provider.clone(),
+        ))
+        .telemetry(ClientTelemetry)
+        .build()?;
+
+    // The frames of the batches after the safe head may have been posted up to a channel timeout
+    // before its L1 origin, so derivation starts that far back. The batches of blocks up to the
+    // safe head are dropped by the batch queue.
+    let reset_number = safe_head
+        .l1_origin
+        .number
+        .saturating_sub(rollup_config.channel_timeout)
+        .max(rollup_config.genesis.l1.number);
+    let reset_origin = block_on(l1_provider.block_info_by_number(reset_number))?;
+    block_on(pipeline.reset(reset_origin, system_config))
+        .map_err(|e| anyhow!("Failed to reset the derivation pipeline: {e}"))?;
+
+    let mut message_passer_storage_root = agreed_output.message_passer_storage_root;
+    while safe_head.block_info.number < claimed_number {
+        let attributes = match block_on(pipeline.step(safe_head)) {
+            Ok(attributes) => attributes.attributes,
+            Err(StageError::BlockInfoFetch(e)) if e.downcast_ref::<PastL1Head>().is_some() => {
+                break;
+            }
+            Err(e) => {
+                bail!("Failed to derive L2 block {}: {e}", safe_head.block_info.number + 1)
+            }
+        };
+
+        let (_, parent) = l2_provider.header_by_number(safe_head.block_info.number)?;
+        let mut execute = |attributes: &PayloadAttributes| {
+            oracle.with_parts(|oracle, hint_writer| {
+                executor.execute(
+                    oracle,
+                    hint_writer,
+                    &rollup_config,
+                    &chain_config,
+                    &parent,
+                    attributes,
+                )
+            })
+        };
+        let block = match execute(&attributes) {
+            // If a transaction of the batch cannot be included, the block is replaced with one
+            // that only includes the deposits, as the rollup node does.
+            Err(e) if e.downcast_ref::<InvalidTransaction>().is_some() => {
+                let transactions = attributes
+                    .transactions
+                    .iter()
+                    .filter(|tx| tx.is_deposit())
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if transactions.len() == attributes.transactions.len() {
+                    bail!("Failed to execute L2 block {}: {e}", parent.number + 1);
+                }
+                execute(&PayloadAttributes { transactions, no_tx_pool: true, ..attributes })?
+            }
+            result => result?,
+        };
+        message_passer_storage_root = block.message_passer_storage_root;
+
+        let number = block.header.number;
+        l2_provider.insert_executed(block.header, block.transactions);
+        safe_head = block_on(l2_provider.l2_block_info_by_number(number))?;
+    }
+
+    let (block_hash, header) = l2_provider.header_by_number(safe_head.block_info.number)?;
+    let output_root =
+        OutputRoot { state_root: header.state_root, message_passer_storage_root, block_hash };
+    Ok(output_root.hash() == claimed_output_root)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mpt::{bytes_to_nibbles, ordered_trie_root, TrieNode, EMPTY_ROOT};
+    use alloc::{collections::BTreeMap, vec};
+    use alloy_consensus::Header;
+    use alloy_primitives::{address, keccak256, Bloom, U256};
+    use alloy_rlp::{Encodable, RlpEncodable};
+    use kona_derive::types::{
+        BlockID, Genesis, L1BlockInfoTx, RawTransaction, SystemAccounts, SystemConfig,
+    };
+    use kona_preimage::{
+        BundleOracle, KeyValueStore, PreimageBundle, PreimageKey, PreimageKeyType, L1_HEAD_KEY,
+        L2_ROLLUP_CONFIG_KEY,
+    };
+
+    /// The storage root of the `L2ToL1MessagePasser` contract in the agreed upon L2 block.
+    const MESSAGE_PASSER_STORAGE_ROOT: B256 = B256::repeat_byte(0xaa);
+
+    /// The code of a contract that stores 1 in its slot 0: `PUSH1 1 PUSH1 0 SSTORE STOP`.
+    const STORE_CODE: [u8; 6] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x00];
+
+    /// An account as it is encoded in the state trie.
+    #[derive(RlpEncodable)]
+    struct TrieAccount {
+        nonce: u64,
+        balance: U256,
+        storage_root: B256,
+        code_hash: B256,
+    }
+
+    /// Inserts `value` into `trie` at the keccak256 hash of `key`.
+    fn insert(trie: &mut TrieNode, key: &[u8], value: Vec<u8>) {
+        let path = bytes_to_nibbles(keccak256(key).as_slice());
+        trie.insert(&path, value, &mut |_| bail!("No nodes")).unwrap();
+    }
+
+    /// Returns the header and hash of an empty block.
+    fn empty_block(number: u64, timestamp: u64) -> (Header, B256) {
+        let header = Header {
+            number,
+            timestamp,
+            transactions_root: EMPTY_ROOT,
+            receipts_root: EMPTY_ROOT,
+            ..Default::default()
+        };
+        let hash = header.hash_slow();
+        (header, hash)
+    }
+
+    /// Returns an oracle that boots the program with `boot_info`, and serves `preimages` by their
+    /// keccak256 hashes.
+    fn oracle_with(
+        boot_info: &BootInfo,
+        preimages: impl IntoIterator<Item = Vec<u8>>,
+    ) -> BundleOracle {
+        let mut bundle = PreimageBundle::new();
+        for ident in L1_HEAD_KEY..=L2_ROLLUP_CONFIG_KEY {
+            let key = PreimageKey::new_local(ident);
+            bundle.insert_preimage(key, boot_info.get(key).unwrap().unwrap());
+        }
+        for preimage in preimages {
+            let key = PreimageKey::new(*keccak256(&preimage), PreimageKeyType::Keccak256);
+            bundle.insert_preimage(key, preimage);
+        }
+        BundleOracle::new(bundle)
+    }
+
+    /// Returns an oracle for a chain that starts at an empty L2 genesis block, which is also the
+    /// agreed upon L2 block, and an L1 head that is the L1 genesis block.
+    fn boot_oracle(claimed_number: u64, claim: Option<B256>) -> BundleOracle {
+        let (l1_header, l1_hash) = empty_block(100, 1_000);
+        let (l2_header, l2_hash) = empty_block(0, 1_000);
+        let output = OutputRoot {
+            state_root: l2_header.state_root,
+            message_passer_storage_root: MESSAGE_PASSER_STORAGE_ROOT,
+            block_hash: l2_hash,
+        };
+        let output_root = output.hash();
+
+        let rollup_config = RollupConfig {
+            genesis: Genesis {
+                l1: BlockID { hash: l1_hash, number: l1_header.number },
+                l2: BlockID { hash: l2_hash, number: l2_header.number },
+                timestamp: l2_header.timestamp,
+                ..Default::default()
+            },
+            block_time: 2,
+            seq_window_size: 3_600,
+            channel_timeout: 300,
+            l2_chain_id: 10,
+            // The L1 data source is chosen by the Ecotone activation time.
+            ecotone_time: Some(1_000_000),
+            ..Default::default()
+        };
+        let boot_info = BootInfo {
+            l1_head: *l1_hash,
+            l2_output_root: *output_root,
+            l2_claim: *claim.unwrap_or(output_root),
+            l2_claim_block_number: claimed_number,
+            l2_chain_id: 10,
+            l2_chain_config: b"{}".to_vec(),
+            rollup_config: serde_json::to_vec(&rollup_config).unwrap(),
+        };
+
+        let preimages = [
+            output.encode().to_vec(),
+            alloy_rlp::encode(&l1_header),
+            alloy_rlp::encode(&l2_header),
+        ];
+        oracle_with(&boot_info, preimages)
+    }
+
+    /// Checks a claim over the oracle, which also serves as the hint writer.
+    fn check(oracle: BundleOracle) -> Result<bool> {
+        check_claim(oracle.clone(), oracle, UnavailableExecutor)
+    }
+
+    #[test]
+    fn test_claim_at_agreed_block() {
+        let oracle = boot_oracle(0, None);
+        assert!(check(oracle).unwrap());
+
+        let oracle = boot_oracle(0, Some(B256::repeat_byte(1)));
+        assert!(!check(oracle).unwrap());
+    }
+
+    #[test]
+    fn test_claim_without_l1_data() {
+        // The L1 head is the L1 origin of the agreed upon block, so no new L2 blocks can be
+        // derived, and the claim is checked against the agreed upon output root.
+        let oracle = boot_oracle(5, None);
+        assert!(check(oracle).unwrap());
+
+        let oracle = boot_oracle(5, Some(B256::repeat_byte(1)));
+        assert!(!check(oracle).unwrap());
+    }
+
+    #[test]
+    fn test_claim_chain_id_mismatch() {
+        let oracle = boot_oracle(0, None);
+        let mut bundle = oracle.bundle().clone();
+        bundle.insert_preimage(
+            PreimageKey::new_local(kona_preimage::L2_CHAIN_ID_KEY),
+            11u64.to_be_bytes().to_vec(),
+        );
+        assert!(check(BundleOracle::new(bundle)).is_err());
+    }
+
+    #[test]
+    fn test_unavailable_executor() {
+        let mut oracle = boot_oracle(0, None);
+        let hint_writer = oracle.clone();
+        let parent = empty_block(0, 1_000).0;
+        let result = UnavailableExecutor.execute(
+            &mut oracle,
+            &hint_writer,
+            &Default::default(),
+            &Default::default(),
+            &parent,
+            &Default::default(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_claim_one_block_past_agreed_block() {
+        let accounts = SystemAccounts::default();
+        let message_passer = address!("4200000000000000000000000000000000000016");
+
+        // The L1 chain runs past the sequencing window of the L1 origin of the L2 genesis block
+        // without any batches, so the first L2 block is derived from an empty batch.
+        let mut l1_headers: Vec<Header> = Vec::new();
+        for number in 100..=110 {
+            l1_headers.push(Header {
+                parent_hash: l1_headers.last().map_or(B256::ZERO, Header::hash_slow),
+                number,
+                timestamp: 1_000 + 12 * (number - 100),
+                transactions_root: EMPTY_ROOT,
+                receipts_root: EMPTY_ROOT,
+                mix_hash: B256::repeat_byte(0x77),
+                base_fee_per_gas: Some(7),
+                ..Default::default()
+            });
+        }
+        let l1_origin = &l1_headers[0];
+        let l1_origin_hash = l1_origin.hash_slow();
+
+        // The genesis state holds the `L1Block` predeploy, which the L1 info deposit calls, and
+        // the storage of the `L2ToL1MessagePasser`. Neither the account of the depositor nor the
+        // storage of the predeploy exist yet.
+        let mut message_passer_storage = TrieNode::Empty;
+        insert(
+            &mut message_passer_storage,
+            &U256::from(1).to_be_bytes::<32>(),
+            alloy_rlp::encode(U256::from(0xff)),
+        );
+        let message_passer_account = TrieAccount {
+            nonce: 1,
+            balance: U256::ZERO,
+            storage_root: message_passer_storage.hash(),
+            code_hash: keccak256([]),
+        };
+        let l1_block_account = |storage_root| TrieAccount {
+            nonce: 1,
+            balance: U256::ZERO,
+            storage_root,
+            code_hash: keccak256(STORE_CODE),
+        };
+        let mut genesis_state = TrieNode::Empty;
+        insert(
+            &mut genesis_state,
+            message_passer.as_slice(),
+            alloy_rlp::encode(message_passer_account),
+        );
+        insert(
+            &mut genesis_state,
+            accounts.attributes_predeploy.as_slice(),
+            alloy_rlp::encode(l1_block_account(EMPTY_ROOT)),
+        );
+
+        let genesis = Header {
+            number: 0,
+            timestamp: 1_000,
+            state_root: genesis_state.hash(),
+            transactions_root: EMPTY_ROOT,
+            receipts_root: EMPTY_ROOT,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        let genesis_hash = genesis.hash_slow();
+        let agreed_output = OutputRoot {
+            state_root: genesis.state_root,
+            message_passer_storage_root: message_passer_storage.hash(),
+            block_hash: genesis_hash,
+        };
+
+        let rollup_config = RollupConfig {
+            genesis: Genesis {
+                l1: BlockID { hash: l1_origin_hash, number: l1_origin.number },
+                l2: BlockID { hash: genesis_hash, number: 0 },
+                timestamp: genesis.timestamp,
+                system_config: SystemConfig {
+                    gas_limit: U256::from(30_000_000),
+                    ..Default::default()
+                },
+            },
+            block_time: 2,
+            seq_window_size: 4,
+            channel_timeout: 300,
+            l2_chain_id: 10,
+            regolith_time: Some(0),
+            canyon_time: Some(0),
+            ecotone_time: Some(1_000_000),
+            ..Default::default()
+        };
+        let chain_config = br#"{"optimism":{"eip1559Elasticity":6,"eip1559Denominator":50,"eip1559DenominatorCanyon":250}}"#;
+
+        // Block 1 is in the epoch of the L1 origin of the genesis block, and only includes the L1
+        // info deposit, which stores 1 in the `L1Block` predeploy.
+        let (_, deposit) = L1BlockInfoTx::try_new_with_deposit_tx(
+            &rollup_config,
+            &rollup_config.genesis.system_config,
+            1,
+            l1_origin,
+            l1_origin_hash,
+            1_002,
+        )
+        .unwrap();
+        let calldata_gas: u64 = deposit.input.iter().map(|b| if *b == 0 { 4 } else { 16 }).sum();
+        // The intrinsic gas, two pushes, and the store of a new value to a cold slot.
+        let gas_used = 21_000 + calldata_gas + 3 + 3 + 22_100;
+        let deposit = RawTransaction::from(deposit).0;
+
+        let mut receipt_fields = vec![];
+        true.encode(&mut receipt_fields);
+        gas_used.encode(&mut receipt_fields);
+        Bloom::ZERO.encode(&mut receipt_fields);
+        receipt_fields.push(alloy_rlp::EMPTY_LIST_CODE);
+        // The nonce of the depositor, and the receipt version from Canyon on.
+        0u64.encode(&mut receipt_fields);
+        1u64.encode(&mut receipt_fields);
+        let mut receipt = vec![0x7e];
+        alloy_rlp::Header { list: true, payload_length: receipt_fields.len() }.encode(&mut receipt);
+        receipt.extend_from_slice(&receipt_fields);
+
+        let mut l1_block_storage = TrieNode::Empty;
+        insert(
+            &mut l1_block_storage,
+            &U256::ZERO.to_be_bytes::<32>(),
+            alloy_rlp::encode(U256::from(1)),
+        );
+        let mut state = genesis_state.clone();
+        insert(
+            &mut state,
+            accounts.attributes_predeploy.as_slice(),
+            alloy_rlp::encode(l1_block_account(l1_block_storage.hash())),
+        );
+        insert(
+            &mut state,
+            accounts.attributes_depositor.as_slice(),
+            alloy_rlp::encode(TrieAccount {
+                nonce: 1,
+                balance: U256::ZERO,
+                storage_root: EMPTY_ROOT,
+                code_hash: keccak256([]),
+            }),
+        );
+
+        let block = Header {
+            parent_hash: genesis_hash,
+            ommers_hash: keccak256([alloy_rlp::EMPTY_LIST_CODE]),
+            beneficiary: accounts.fee_vault,
+            state_root: state.hash(),
+            transactions_root: ordered_trie_root(&[deposit]).unwrap(),
+            receipts_root: ordered_trie_root(&[receipt]).unwrap(),
+            withdrawals_root: Some(EMPTY_ROOT),
+            number: 1,
+            gas_limit: 30_000_000,
+            gas_used,
+            timestamp: 1_002,
+            mix_hash: l1_origin.mix_hash,
+            // The gas target of 5M was not used, which lowers the base fee by 1/250.
+            base_fee_per_gas: Some(996_000_000),
+            ..Default::default()
+        };
+        let claimed_output = OutputRoot {
+            state_root: block.state_root,
+            message_passer_storage_root: message_passer_storage.hash(),
+            block_hash: block.hash_slow(),
+        };
+
+        let mut preimages = BTreeMap::new();
+        genesis_state.collect_nodes(&mut preimages);
+        message_passer_storage.collect_nodes(&mut preimages);
+        let preimages = preimages
+            .into_values()
+            .chain(l1_headers.iter().map(alloy_rlp::encode))
+            .chain([
+                alloy_rlp::encode(&genesis),
+                agreed_output.encode().to_vec(),
+                STORE_CODE.to_vec(),
+            ])
+            .collect::<Vec<_>>();
+
+        for (claim, valid) in [(claimed_output.hash(), true), (agreed_output.hash(), false)] {
+            let boot_info = BootInfo {
+                l1_head: *l1_headers.last().unwrap().hash_slow(),
+                l2_output_root: *agreed_output.hash(),
+                l2_claim: *claim,
+                l2_claim_block_number: 1,
+                l2_chain_id: 10,
+                l2_chain_config: chain_config.to_vec(),
+                rollup_config: serde_json::to_vec(&rollup_config).unwrap(),
+            };
+            let oracle = oracle_with(&boot_info, preimages.clone());
+            let result = check_claim(oracle.clone(), oracle, StatelessExecutor::new());
+            assert_eq!(result.unwrap(), valid);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! The entry point of the OP Stack fault proof program, which runs [kona_client::check_claim] over
+//! the preimage oracle and hint pipes of the Fault Proof VM.
+//!
+//! **Exit codes:**
+//! - `0`: The claim is valid.
+//! - `1`: The claim is invalid, or could not be proven.
+
+#![warn(missing_debug_implementations, missing_docs, unreachable_pub, rustdoc::all)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![cfg_attr(any(target_arch = "mips", target_arch = "riscv64"), no_std, no_main)]
+
+extern crate alloc;
+
+use kona_client::StatelessExecutor;
+use kona_common::{io, FileDescriptor};
+use kona_preimage::{HintWriter, OracleReader, PipeHandle};
+
+/// The pipe that preimages are requested and read over.
+static CLIENT_PREIMAGE_PIPE: PipeHandle =
+    PipeHandle::new(FileDescriptor::PreimageRead, FileDescriptor::PreimageWrite);
+
+/// The pipe that hints are written over.
+static CLIENT_HINT_PIPE: PipeHandle =
+    PipeHandle::new(FileDescriptor::HintRead, FileDescriptor::HintWrite);
+
+cfg_if::cfg_if! {
+    if #[cfg(any(target_arch = "mips", target_arch = "riscv64"))] {
+        /// The size of the heap of the program, in bytes.
+        const HEAP_SIZE: usize = 0xFFFFFFF;
+
+        #[no_mangle]
+        extern "C" fn _start() {
+            kona_common::alloc_heap!(HEAP_SIZE);
+            run()
+        }
+
+        #[panic_handler]
+        fn panic(info: &core::panic::PanicInfo<'_>) -> ! {
+            let msg = alloc::format!("Panic: {}\n", info);
+            io::print_err(msg.as_ref());
+            io::exit(1)
+        }
+    } else {
+        fn main() {
+            run()
+        }
+    }
+}
+
+/// Runs the program and exits with the verdict on the claim.
+fn run() -> ! {
+    let oracle = OracleReader::new(CLIENT_PREIMAGE_PIPE);
+    let hint_writer = HintWriter::new(CLIENT_HINT_PIPE);
+
+    match kona_client::check_claim(oracle, hint_writer, StatelessExecutor::new()) {
+        Ok(true) => {
+            io::print("Claim is valid\n");
+            io::exit(0)
+        }
+        Ok(false) => {
+            io::print_err("Claim is invalid\n");
+            io::exit(1)
+        }
+        Err(e) => {
+            io::print_err(alloc::format!("Failed to prove the claim: {e}\n").as_ref());
+            io::exit(1)
+        }
+    }
+}
@@ -0,0 +1,683 @@
+//! Contains a reader for the ordered Merkle Patricia tries that the transactions and receipts of a
+//! block are committed to, keyed by the RLP encoding of their index in the block, and the
+//! [TrieNode], a Merkle Patricia trie that is fetched lazily as it is read and modified.
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use alloy_primitives::{b256, keccak256, B256};
+use alloy_rlp::{Decodable, Header, EMPTY_STRING_CODE};
+use anyhow::{anyhow, bail, ensure, Result};
+use core::{iter, mem};
+
+/// The root of an empty trie, `keccak256(rlp(""))`.
+pub(crate) const EMPTY_ROOT: B256 =
+    b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+
+/// Reads every value of the ordered trie with the given root, in the order of their indices.
+///
+/// The trie nodes that are referenced by hash are fetched with `fetch`, which returns the preimage
+/// of a node hash.
+///
+/// # Returns
+/// - `Ok(Vec<Vec<u8>>)` with the values of the trie.
+/// - `Err(_)` if a node could not be fetched or decoded, or the keys of the trie are not the
+///   indices `0..n`.
+pub(crate) fn ordered_trie_values<F>(root: B256, mut fetch: F) -> Result<Vec<Vec<u8>>>
+where
+    F: FnMut(B256) -> Result<Vec<u8>>,
+{
+    if root == EMPTY_ROOT {
+        return Ok(Vec::new());
+    }
+
+    let mut leaves = Vec::new();
+    let root_node = fetch(root)?;
+    walk(&root_node, &mut Vec::new(), &mut fetch, &mut leaves)?;
+
+    let mut values = leaves
+        .into_iter()
+        .map(|(path, value)| {
+            let key = nibbles_to_bytes(&path)?;
+            let index = u64::decode(&mut key.as_slice())
+                .map_err(|e| anyhow!("Invalid ordered trie key {key:?}: {e}"))?;
+            Ok((index, value))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    values.sort_unstable_by_key(|(index, _)| *index);
+
+    for (expected, (index, _)) in values.iter().enumerate() {
+        ensure!(*index == expected as u64, "Ordered trie is missing the value at index {expected}");
+    }
+    Ok(values.into_iter().map(|(_, value)| value).collect())
+}
+
+/// Computes the root of the ordered trie of the given values, keyed by the RLP encoding of their
+/// index.
+pub(crate) fn ordered_trie_root<T: AsRef<[u8]>>(values: &[T]) -> Result<B256> {
+    let mut trie = TrieNode::Empty;
+    for (index, value) in values.iter().enumerate() {
+        let key = bytes_to_nibbles(&alloy_rlp::encode(index));
+        trie.insert(&key, value.as_ref().to_vec(), &mut |hash| bail!("Missing trie node {hash}"))?;
+    }
+    Ok(trie.hash())
+}
+
+/// A node of a Merkle Patricia trie. The nodes that are referenced by hash are only fetched when
+/// they are first read or modified, so that only the parts of the trie that are used are loaded.
+///
+/// The paths of the trie are sequences of nibbles, and the values are never empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) enum TrieNode {
+    /// The empty trie.
+    #[default]
+    Empty,
+    /// A node that is referenced by its hash, and has not been fetched yet.
+    Blinded(B256),
+    /// A leaf node, holding the value at the remainder of its path.
+    Leaf {
+        /// The remainder of the path of the value.
+        path: Vec<u8>,
+        /// The value.
+        value: Vec<u8>,
+    },
+    /// An extension node, holding the path that all values below it share.
+    Extension {
+        /// The shared path.
+        path: Vec<u8>,
+        /// The node below the shared path, a branch.
+        child: Box<TrieNode>,
+    },
+    /// A branch node, with a child for each nibble.
+    Branch {
+        /// The children of the branch, by nibble.
+        children: Box<[TrieNode; 16]>,
+        /// The value at the path of the branch, empty if there is none.
+        value: Vec<u8>,
+    },
+}
+
+impl TrieNode {
+    /// Creates a trie with the given root, which is fetched on first use.
+    pub(crate) fn new(root: B256) -> Self {
+        if root == EMPTY_ROOT {
+            Self::Empty
+        } else {
+            Self::Blinded(root)
+        }
+    }
+
+    /// Decodes an RLP encoded trie node.
+    pub(crate) fn decode(encoded: &[u8]) -> Result<Self> {
+        let items = list_items(encoded)?;
+        match items.len() {
+            17 => {
+                let mut children = Box::<[Self; 16]>::default();
+                for (child, item) in children.iter_mut().zip(&items[..16]) {
+                    *child = Self::decode_reference(item)?;
+                }
+                Ok(Self::Branch { children, value: string_payload(items[16])?.to_vec() })
+            }
+            2 => {
+                let (path, is_leaf) = decode_hex_prefix(string_payload(items[0])?)?;
+                if is_leaf {
+                    return Ok(Self::Leaf { path, value: string_payload(items[1])?.to_vec() });
+                }
+                let child = Self::decode_reference(items[1])?;
+                ensure!(child != Self::Empty, "Extension node without a child");
+                Ok(Self::Extension { path, child: Box::new(child) })
+            }
+            n => bail!("Invalid trie node with {n} items"),
+        }
+    }
+
+    /// Decodes a reference to a child node, which is either the node itself if its encoding is
+    /// shorter than 32 bytes, or its hash.
+    fn decode_reference(item: &[u8]) -> Result<Self> {
+        if item.first().is_some_and(|b| *b >= alloy_rlp::EMPTY_LIST_CODE) {
+            return Self::decode(item);
+        }
+        match string_payload(item)? {
+            [] => Ok(Self::Empty),
+            hash if hash.len() == 32 => Ok(Self::Blinded(B256::from_slice(hash))),
+            other => bail!("Invalid trie node reference of length {}", other.len()),
+        }
+    }
+
+    /// Fetches the node with `fetch` if it is blinded.
+    fn resolve<F>(&mut self, fetch: &mut F) -> Result<()>
+    where
+        F: FnMut(B256) -> Result<Vec<u8>>,
+    {
+        if let Self::Blinded(hash) = *self {
+            *self = Self::decode(&fetch(hash)?)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the value at the given path, fetching the nodes along it with `fetch`.
+    pub(crate) fn get<F>(&mut self, path: &[u8], fetch: &mut F) -> Result<Option<Vec<u8>>>
+    where
+        F: FnMut(B256) -> Result<Vec<u8>>,
+    {
+        self.resolve(fetch)?;
+        match self {
+            Self::Empty => Ok(None),
+            Self::Blinded(_) => unreachable!("Resolved trie nodes are not blinded"),
+            Self::Leaf { path: leaf_path, value } => {
+                Ok((leaf_path.as_slice() == path).then(|| value.clone()))
+            }
+            Self::Extension { path: prefix, child } => match path.strip_prefix(prefix.as_slice()) {
+                Some(rest) => child.get(rest, fetch),
+                None => Ok(None),
+            },
+            Self::Branch { children, value } => match path.split_first() {
+                Some((nibble, rest)) => children[*nibble as usize].get(rest, fetch),
+                None => Ok((!value.is_empty()).then(|| value.clone())),
+            },
+        }
+    }
+
+    /// Sets the value at the given path, fetching the nodes along it with `fetch`.
+    pub(crate) fn insert<F>(&mut self, path: &[u8], value: Vec<u8>, fetch: &mut F) -> Result<()>
+    where
+        F: FnMut(B256) -> Result<Vec<u8>>,
+    {
+        self.resolve(fetch)?;
+        match self {
+            Self::Empty => *self = Self::Leaf { path: path.to_vec(), value },
+            Self::Blinded(_) => unreachable!("Resolved trie nodes are not blinded"),
+            Self::Leaf { path: leaf_path, value: leaf_value } => {
+                if leaf_path.as_slice() == path {
+                    *leaf_value = value;
+                    return Ok(());
+                }
+                // Split the leaf into a branch at the end of the common path.
+                let common = common_prefix_len(leaf_path, path);
+                let mut branch = Self::Branch { children: Default::default(), value: Vec::new() };
+                branch.insert(&leaf_path[common..], mem::take(leaf_value), fetch)?;
+                branch.insert(&path[common..], value, fetch)?;
+                *self = Self::with_prefix(&leaf_path[..common], branch);
+            }
+            Self::Extension { path: prefix, child } => {
+                if let Some(rest) = path.strip_prefix(prefix.as_slice()) {
+                    return child.insert(rest, value, fetch);
+                }
+                // Split the extension into a branch at the end of the common path.
+                let common = common_prefix_len(prefix, path);
+                let mut children = Box::<[Self; 16]>::default();
+                children[prefix[common] as usize] =
+                    Self::with_prefix(&prefix[common + 1..], mem::take(child));
+                let mut branch = Self::Branch { children, value: Vec::new() };
+                branch.insert(&path[common..], value, fetch)?;
+                *self = Self::with_prefix(&prefix[..common], branch);
+            }
+            Self::Branch { children, value: branch_value } => match path.split_first() {
+                Some((nibble, rest)) => children[*nibble as usize].insert(rest, value, fetch)?,
+                None => *branch_value = value,
+            },
+        }
+        Ok(())
+    }
+
+    /// Removes the value at the given path, if there is one, fetching the nodes along it with
+    /// `fetch`. A branch that is left with a single child is merged with it, which may need the
+    /// child to be fetched.
+    pub(crate) fn delete<F>(&mut self, path: &[u8], fetch: &mut F) -> Result<()>
+    where
+        F: FnMut(B256) -> Result<Vec<u8>>,
+    {
+        self.resolve(fetch)?;
+        match self {
+            Self::Empty => {}
+            Self::Blinded(_) => unreachable!("Resolved trie nodes are not blinded"),
+            Self::Leaf { path: leaf_path, .. } => {
+                if leaf_path.as_slice() == path {
+                    *self = Self::Empty;
+                }
+            }
+            Self::Extension { path: prefix, child } => {
+                if let Some(rest) = path.strip_prefix(prefix.as_slice()) {
+                    child.delete(rest, fetch)?;
+                    let prefix = mem::take(prefix);
+                    *self = Self::with_prefix(&prefix, mem::take(child));
+                }
+            }
+            Self::Branch { children, value } => {
+                match path.split_first() {
+                    Some((nibble, rest)) => children[*nibble as usize].delete(rest, fetch)?,
+                    None => value.clear(),
+                }
+
+                let mut remaining = children.iter().enumerate().filter(|(_, c)| **c != Self::Empty);
+                match (remaining.next(), remaining.next(), value.is_empty()) {
+                    (None, _, true) => *self = Self::Empty,
+                    (None, _, false) => {
+                        *self = Self::Leaf { path: Vec::new(), value: mem::take(value) }
+                    }
+                    (Some((nibble, _)), None, true) => {
+                        let mut child = mem::take(&mut children[nibble]);
+                        child.resolve(fetch)?;
+                        *self = Self::with_prefix(&[nibble as u8], child);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Prepends `prefix` to the path of `node`, merging it into the path of a leaf or extension,
+    /// or adding an extension in front of a branch.
+    fn with_prefix(prefix: &[u8], node: Self) -> Self {
+        match node {
+            Self::Leaf { path, value } => Self::Leaf { path: [prefix, &path].concat(), value },
+            Self::Extension { path, child } => {
+                Self::Extension { path: [prefix, &path].concat(), child }
+            }
+            node if prefix.is_empty() || node == Self::Empty => node,
+            node => Self::Extension { path: prefix.to_vec(), child: Box::new(node) },
+        }
+    }
+
+    /// Returns the root hash of the trie.
+    pub(crate) fn hash(&self) -> B256 {
+        match self {
+            Self::Empty => EMPTY_ROOT,
+            Self::Blinded(hash) => *hash,
+            node => keccak256(node.encode()),
+        }
+    }
+
+    /// RLP encodes the node. A blinded node is encoded as its hash.
+    fn encode(&self) -> Vec<u8> {
+        let items = match self {
+            Self::Empty => return vec![EMPTY_STRING_CODE],
+            Self::Blinded(hash) => return alloy_rlp::encode(hash),
+            Self::Leaf { path, value } => {
+                vec![
+                    alloy_rlp::encode(&encode_hex_prefix(path, true)[..]),
+                    alloy_rlp::encode(&value[..]),
+                ]
+            }
+            Self::Extension { path, child } => {
+                vec![alloy_rlp::encode(&encode_hex_prefix(path, false)[..]), child.reference()]
+            }
+            Self::Branch { children, value } => children
+                .iter()
+                .map(Self::reference)
+                .chain(iter::once(alloy_rlp::encode(&value[..])))
+                .collect(),
+        };
+
+        let payload = items.concat();
+        let mut out = Vec::with_capacity(payload.len() + 3);
+        Header { list: true, payload_length: payload.len() }.encode(&mut out);
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    /// Returns the reference to the node from its parent, which is the node itself if its
+    /// encoding is shorter than 32 bytes, or its hash.
+    fn reference(&self) -> Vec<u8> {
+        match self {
+            Self::Blinded(hash) => alloy_rlp::encode(hash),
+            node => {
+                let encoded = node.encode();
+                if encoded.len() < 32 {
+                    encoded
+                } else {
+                    alloy_rlp::encode(keccak256(encoded))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+impl TrieNode {
+    /// Collects the encodings of the nodes of the trie, by hash, so that tests can serve them as
+    /// preimages.
+    pub(crate) fn collect_nodes(&self, nodes: &mut alloc::collections::BTreeMap<B256, Vec<u8>>) {
+        let encoded = self.encode();
+        nodes.insert(keccak256(&encoded), encoded);
+        match self {
+            Self::Extension { child, .. } => child.collect_nodes(nodes),
+            Self::Branch { children, .. } => {
+                children.iter().for_each(|child| child.collect_nodes(nodes))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Walks the trie node `node`, found at the nibble path `path`, collecting the values of its
+/// leaves along with their full paths.
+fn walk<F>(
+    node: &[u8],
+    path: &mut Vec<u8>,
+    fetch: &mut F,
+    leaves: &mut Vec<(Vec<u8>, Vec<u8>)>,
+) -> Result<()>
+where
+    F: FnMut(B256) -> Result<Vec<u8>>,
+{
+    let items = list_items(node)?;
+    match items.len() {
+        // A branch node, with a child for each nibble and a value.
+        17 => {
+            for (nibble, item) in items[..16].iter().enumerate() {
+                if let Some(child) = child_node(item, fetch)? {
+                    path.push(nibble as u8);
+                    walk(&child, path, fetch, leaves)?;
+                    path.pop();
+                }
+            }
+            let value = string_payload(items[16])?;
+            if !value.is_empty() {
+                leaves.push((path.clone(), value.to_vec()));
+            }
+        }
+        // A leaf or extension node, with a hex-prefix encoded path.
+        2 => {
+            let (nibbles, is_leaf) = decode_hex_prefix(string_payload(items[0])?)?;
+            let len = path.len();
+            path.extend_from_slice(&nibbles);
+            if is_leaf {
+                leaves.push((path.clone(), string_payload(items[1])?.to_vec()));
+            } else {
+                let child = child_node(items[1], fetch)?
+                    .ok_or_else(|| anyhow!("Extension node without a child"))?;
+                walk(&child, path, fetch, leaves)?;
+            }
+            path.truncate(len);
+        }
+        n => bail!("Invalid trie node with {n} items"),
+    }
+    Ok(())
+}
+
+/// Resolves a reference to a child node, which is either the node itself if its encoding is
+/// shorter than 32 bytes, or its hash.
+///
+/// # Returns
+/// - `Ok(Some(node))` with the encoded child node.
+/// - `Ok(None)` if the reference is empty.
+/// - `Err(_)` if the reference is invalid, or the node could not be fetched.
+fn child_node<F>(item: &[u8], fetch: &mut F) -> Result<Option<Vec<u8>>>
+where
+    F: FnMut(B256) -> Result<Vec<u8>>,
+{
+    if item.first().is_some_and(|b| *b >= alloy_rlp::EMPTY_LIST_CODE) {
+        return Ok(Some(item.to_vec()));
+    }
+    match string_payload(item)? {
+        [] => Ok(None),
+        hash if hash.len() == 32 => fetch(B256::from_slice(hash)).map(Some),
+        other => bail!("Invalid trie node reference of length {}", other.len()),
+    }
+}
+
+/// Splits an RLP encoded list into the RLP encodings of its items.
+fn list_items(encoded: &[u8]) -> Result<Vec<&[u8]>> {
+    let mut buf = encoded;
+    let header = Header::decode(&mut buf).map_err(|e| anyhow!("Invalid trie node: {e}"))?;
+    ensure!(header.list, "Trie node is not a list");
+    ensure!(buf.len() >= header.payload_length, "Trie node is truncated");
+
+    let mut payload = &buf[..header.payload_length];
+    let mut items = Vec::with_capacity(17);
+    while !payload.is_empty() {
+        let mut rest = payload;
+        let item = Header::decode(&mut rest).map_err(|e| anyhow!("Invalid trie node item: {e}"))?;
+        let len = payload.len() - rest.len() + item.payload_length;
+        ensure!(len <= payload.len(), "Trie node item is truncated");
+        items.push(&payload[..len]);
+        payload = &payload[len..];
+    }
+    Ok(items)
+}
+
+/// Returns the payload of an RLP encoded string.
+fn string_payload(encoded: &[u8]) -> Result<&[u8]> {
+    let mut buf = encoded;
+    let header = Header::decode(&mut buf).map_err(|e| anyhow!("Invalid trie node item: {e}"))?;
+    ensure!(!header.list, "Expected a string trie node item, got a list");
+    buf.get(..header.payload_length).ok_or_else(|| anyhow!("Trie node item is truncated"))
+}
+
+/// Decodes a hex-prefix encoded path into its nibbles, and whether it belongs to a leaf node.
+fn decode_hex_prefix(encoded: &[u8]) -> Result<(Vec<u8>, bool)> {
+    let Some((&prefix, rest)) = encoded.split_first() else {
+        bail!("Empty hex-prefix encoded path");
+    };
+    let flag = prefix >> 4;
+    ensure!(flag <= 3, "Invalid hex-prefix flag {flag}");
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(prefix & 0x0f);
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+    Ok((nibbles, flag & 2 == 2))
+}
+
+/// Packs a path of nibbles into bytes.
+fn nibbles_to_bytes(nibbles: &[u8]) -> Result<Vec<u8>> {
+    ensure!(nibbles.len() & 1 == 0, "Trie key has an odd number of nibbles");
+    Ok(nibbles.chunks_exact(2).map(|pair| (pair[0] << 4) | pair[1]).collect())
+}
+
+/// Unpacks bytes into their nibbles, the path of a trie key.
+pub(crate) fn bytes_to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+/// Hex-prefix encodes a path of nibbles, flagging whether it belongs to a leaf node.
+fn encode_hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = ((is_leaf as u8) << 1) | (nibbles.len() as u8 & 1);
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() & 1 == 1 {
+        encoded.push((flag << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(flag << 4);
+        nibbles
+    };
+    encoded.extend(rest.chunks_exact(2).map(|pair| (pair[0] << 4) | pair[1]));
+    encoded
+}
+
+/// Returns the length of the common prefix of two paths.
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::collections::BTreeMap;
+
+    /// RLP encodes a list from the RLP encodings of its items.
+    fn list(items: &[Vec<u8>]) -> Vec<u8> {
+        let payload = items.concat();
+        let mut out = Vec::new();
+        Header { list: true, payload_length: payload.len() }.encode(&mut out);
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    /// RLP encodes a string.
+    fn string(bytes: &[u8]) -> Vec<u8> {
+        alloy_rlp::encode(bytes)
+    }
+
+    #[test]
+    fn test_empty_trie() {
+        let values = ordered_trie_values(EMPTY_ROOT, |_| bail!("No nodes")).unwrap();
+        assert!(values.is_empty());
+        assert_eq!(keccak256(string(&[])), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn test_ordered_trie_values() {
+        // The keys are rlp(0) = [8, 0], rlp(1) = [0, 1] and rlp(2) = [0, 2]. The leaf of index 0
+        // is large enough to be referenced by hash, and the branch holding the leaves of indices 1
+        // and 2 is small enough to be inlined into the root.
+        let value_0 = vec![0xaa; 40];
+        let mut nodes = BTreeMap::new();
+        let leaf_0 = list(&[string(&[0x30]), string(&value_0)]);
+        let leaf_0_hash = keccak256(&leaf_0);
+        nodes.insert(leaf_0_hash, leaf_0);
+
+        let mut inner = vec![string(&[]); 17];
+        inner[1] = list(&[string(&[0x20]), string(&[0x01])]);
+        inner[2] = list(&[string(&[0x20]), string(&[0x02, 0x03])]);
+        let inner = list(&inner);
+        assert!(inner.len() < 32);
+
+        let mut root = vec![string(&[]); 17];
+        root[0] = inner;
+        root[8] = string(leaf_0_hash.as_slice());
+        let root = list(&root);
+        let root_hash = keccak256(&root);
+        nodes.insert(root_hash, root);
+
+        let values = ordered_trie_values(root_hash, |hash| {
+            nodes.get(&hash).cloned().ok_or_else(|| anyhow!("Missing node {hash}"))
+        })
+        .unwrap();
+        assert_eq!(values, vec![value_0, vec![0x01], vec![0x02, 0x03]]);
+    }
+
+    #[test]
+    fn test_ordered_trie_missing_index() {
+        // A single leaf at rlp(1) = [0, 1], without a value at index 0.
+        let leaf = list(&[string(&[0x20, 0x01]), string(&[0x01])]);
+        let root_hash = keccak256(&leaf);
+        let result = ordered_trie_values(root_hash, |_| Ok(leaf.clone()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_decode_hex_prefix() {
+        assert_eq!(decode_hex_prefix(&[0x00, 0x12]).unwrap(), (vec![1, 2], false));
+        assert_eq!(decode_hex_prefix(&[0x13, 0x45]).unwrap(), (vec![3, 4, 5], false));
+        assert_eq!(decode_hex_prefix(&[0x20]).unwrap(), (vec![], true));
+        assert_eq!(decode_hex_prefix(&[0x36]).unwrap(), (vec![6], true));
+        assert!(decode_hex_prefix(&[0x40]).is_err());
+        assert!(decode_hex_prefix(&[]).is_err());
+    }
+
+    /// Returns the keys and values of a trie with 100 values at hashed keys.
+    fn hashed_entries() -> Vec<(Vec<u8>, Vec<u8>)> {
+        (0u64..100)
+            .map(|i| {
+                let key = bytes_to_nibbles(keccak256(i.to_be_bytes()).as_slice());
+                (key, vec![i as u8 + 1; (i % 40 + 1) as usize])
+            })
+            .collect()
+    }
+
+    /// Builds a trie from its entries, without fetching any nodes.
+    fn build(entries: &[(Vec<u8>, Vec<u8>)]) -> TrieNode {
+        let mut trie = TrieNode::Empty;
+        for (key, value) in entries {
+            trie.insert(key, value.clone(), &mut |_| bail!("No nodes")).unwrap();
+        }
+        trie
+    }
+
+    #[test]
+    fn test_trie_insert_get() {
+        let entries = hashed_entries();
+        let mut trie = build(&entries);
+        // The root that `alloy-trie` computes for the same entries.
+        assert_eq!(
+            trie.hash(),
+            b256!("811c0e906fc840c7898db72eed1b382b2f1d3fe2b50c9ea8cd27bd5d17645685")
+        );
+        for (key, value) in &entries {
+            assert_eq!(trie.get(key, &mut |_| bail!("No nodes")).unwrap().as_ref(), Some(value));
+        }
+        assert_eq!(trie.get(&[0; 64], &mut |_| bail!("No nodes")).unwrap(), None);
+
+        // Inserting the entries in reverse order results in the same trie.
+        let reversed = entries.into_iter().rev().collect::<Vec<_>>();
+        assert_eq!(build(&reversed), trie);
+    }
+
+    #[test]
+    fn test_trie_delete() {
+        let entries = hashed_entries();
+        let (deleted, kept): (Vec<_>, Vec<_>) =
+            entries.iter().cloned().partition(|(key, _)| ((key[0] << 4) | key[1]) % 3 == 0);
+        let mut trie = build(&entries);
+        for (key, _) in &deleted {
+            trie.delete(key, &mut |_| bail!("No nodes")).unwrap();
+        }
+        // The root that `alloy-trie` computes for the kept entries.
+        assert_eq!(
+            trie.hash(),
+            b256!("4f128e31460c74e40f744cd10725349e3bfcb78e006841b74c700d89df5d9eae")
+        );
+        assert_eq!(trie, build(&kept));
+
+        for (key, _) in &kept {
+            trie.delete(key, &mut |_| bail!("No nodes")).unwrap();
+        }
+        assert_eq!(trie, TrieNode::Empty);
+        assert_eq!(trie.hash(), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn test_trie_fetches_nodes() {
+        let entries = hashed_entries();
+        let full = build(&entries);
+        let mut nodes = BTreeMap::new();
+        full.collect_nodes(&mut nodes);
+        let mut fetch =
+            |hash| nodes.get(&hash).cloned().ok_or_else(|| anyhow!("Missing node {hash}"));
+
+        // Reads fetch the nodes along the path.
+        let mut trie = TrieNode::new(full.hash());
+        assert_eq!(trie.get(&entries[7].0, &mut fetch).unwrap(), Some(entries[7].1.clone()));
+
+        // Deleting all but one value collapses the trie into a single leaf, fetching the siblings
+        // that are merged into their parents.
+        for (key, _) in &entries[1..] {
+            trie.delete(key, &mut fetch).unwrap();
+        }
+        assert_eq!(trie, build(&entries[..1]));
+
+        // Modifications do not need the nodes of the untouched parts of the trie.
+        let mut trie = TrieNode::new(full.hash());
+        let mut modified = entries.clone();
+        modified[3].1 = vec![0xff; 40];
+        trie.insert(&modified[3].0, modified[3].1.clone(), &mut fetch).unwrap();
+        assert!(matches!(&trie, TrieNode::Branch { children, .. }
+            if children.iter().any(|child| matches!(child, TrieNode::Blinded(_)))));
+        assert_eq!(trie.hash(), build(&modified).hash());
+    }
+
+    #[test]
+    fn test_ordered_trie_root() {
+        let values = (0..200).map(|i| vec![0xab; i % 50 + 1]).collect::<Vec<_>>();
+        // The root that `alloy-trie` computes for the same values.
+        assert_eq!(
+            ordered_trie_root(&values).unwrap(),
+            b256!("1c7084cf1309bbaf6403836d9d5f9c2441d52eeae1676c69c1656dff2ea26f2d")
+        );
+        assert_eq!(ordered_trie_root::<Vec<u8>>(&[]).unwrap(), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn test_encode_hex_prefix() {
+        for (nibbles, is_leaf) in [(vec![1, 2], false), (vec![3, 4, 5], false), (vec![], true)] {
+            let encoded = encode_hex_prefix(&nibbles, is_leaf);
+            assert_eq!(decode_hex_prefix(&encoded).unwrap(), (nibbles, is_leaf));
+        }
+        assert_eq!(encode_hex_prefix(&[6], true), vec![0x36]);
+    }
+}
@@ -0,0 +1,64 @@
+//! Contains the [OracleHandle], which shares the preimage oracle and hint writer of the client
+//! program between the providers of the derivation pipeline.
+
+use alloc::{sync::Arc, vec::Vec};
+use anyhow::Result;
+use core::fmt::Debug;
+use kona_preimage::{Hint, HintWriterClient, PreimageKey, PreimageOracleClient};
+use spin::Mutex;
+
+/// A cheaply cloneable handle to the preimage oracle and hint writer of the client program.
+///
+/// Every request to the oracle first sends a [Hint] to the host, so that the host can prepare the
+/// preimages that the request will need.
+pub(crate) struct OracleHandle<O, H> {
+    /// The preimage oracle.
+    oracle: Arc<Mutex<O>>,
+    /// The hint writer.
+    hint_writer: Arc<H>,
+}
+
+impl<O, H> OracleHandle<O, H>
+where
+    O: PreimageOracleClient,
+    H: HintWriterClient,
+{
+    /// Creates a new [OracleHandle] that shares the given oracle and hint writer.
+    pub(crate) fn new(oracle: O, hint_writer: H) -> Self {
+        Self { oracle: Arc::new(Mutex::new(oracle)), hint_writer: Arc::new(hint_writer) }
+    }
+
+    /// Sends the hint to the host, and then fetches the preimage of the key.
+    pub(crate) fn get(&self, hint: &Hint, key: PreimageKey) -> Result<Vec<u8>> {
+        self.with_oracle(hint, |oracle| oracle.get(key))
+    }
+
+    /// Sends the hint to the host, and then runs `f` with exclusive access to the oracle, for
+    /// requests that are made up of several preimages.
+    pub(crate) fn with_oracle<T>(
+        &self,
+        hint: &Hint,
+        f: impl FnOnce(&mut O) -> Result<T>,
+    ) -> Result<T> {
+        let mut oracle = self.oracle.lock();
+        self.hint_writer.write_hint(hint)?;
+        f(&mut oracle)
+    }
+
+    /// Runs `f` with exclusive access to the oracle, and the hint writer.
+    pub(crate) fn with_parts<T>(&self, f: impl FnOnce(&mut O, &H) -> T) -> T {
+        f(&mut self.oracle.lock(), &self.hint_writer)
+    }
+}
+
+impl<O, H> Clone for OracleHandle<O, H> {
+    fn clone(&self) -> Self {
+        Self { oracle: self.oracle.clone(), hint_writer: self.hint_writer.clone() }
+    }
+}
+
+impl<O, H> Debug for OracleHandle<O, H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("OracleHandle").finish_non_exhaustive()
+    }
+}
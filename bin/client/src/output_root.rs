@@ -0,0 +1,76 @@
+//! Contains the [OutputRoot] type, the commitment to an L2 block that the fault proof program
+//! proves claims about.
+
+use alloy_primitives::{keccak256, B256};
+use anyhow::{bail, Result};
+
+/// The version 0 preimage of an output root: the version, the state root, the storage root of the
+/// `L2ToL1MessagePasser` contract and the block hash, each 32 bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct OutputRoot {
+    /// The state root of the L2 block.
+    pub(crate) state_root: B256,
+    /// The storage root of the `L2ToL1MessagePasser` contract after the L2 block.
+    pub(crate) message_passer_storage_root: B256,
+    /// The hash of the L2 block.
+    pub(crate) block_hash: B256,
+}
+
+impl OutputRoot {
+    /// The size of an encoded version 0 output root preimage.
+    pub(crate) const ENCODED_SIZE: usize = 128;
+
+    /// Encodes the [OutputRoot] into its version 0 preimage.
+    pub(crate) fn encode(&self) -> [u8; Self::ENCODED_SIZE] {
+        let mut buf = [0u8; Self::ENCODED_SIZE];
+        buf[32..64].copy_from_slice(self.state_root.as_slice());
+        buf[64..96].copy_from_slice(self.message_passer_storage_root.as_slice());
+        buf[96..].copy_from_slice(self.block_hash.as_slice());
+        buf
+    }
+
+    /// Decodes an [OutputRoot] from its version 0 preimage.
+    pub(crate) fn decode(buf: &[u8]) -> Result<Self> {
+        if buf.len() != Self::ENCODED_SIZE {
+            bail!("Invalid output root preimage length: {}", buf.len());
+        }
+        if buf[..32] != B256::ZERO {
+            bail!("Unsupported output root version: {}", B256::from_slice(&buf[..32]));
+        }
+        Ok(Self {
+            state_root: B256::from_slice(&buf[32..64]),
+            message_passer_storage_root: B256::from_slice(&buf[64..96]),
+            block_hash: B256::from_slice(&buf[96..]),
+        })
+    }
+
+    /// Computes the output root, the keccak256 hash of the version 0 preimage.
+    pub(crate) fn hash(&self) -> B256 {
+        keccak256(self.encode())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_output_root_roundtrip() {
+        let output_root = OutputRoot {
+            state_root: B256::repeat_byte(1),
+            message_passer_storage_root: B256::repeat_byte(2),
+            block_hash: B256::repeat_byte(3),
+        };
+        let encoded = output_root.encode();
+        assert_eq!(OutputRoot::decode(&encoded).unwrap(), output_root);
+        assert_eq!(output_root.hash(), keccak256(encoded));
+    }
+
+    #[test]
+    fn test_output_root_decode_invalid() {
+        assert!(OutputRoot::decode(&[0u8; 127]).is_err());
+        let mut encoded = OutputRoot::default().encode();
+        encoded[31] = 1;
+        assert!(OutputRoot::decode(&encoded).is_err());
+    }
+}
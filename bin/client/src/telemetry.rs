@@ -0,0 +1,19 @@
+//! Contains the [ClientTelemetry], which reports the telemetry of the derivation pipeline.
+
+use alloc::{format, string::String};
+use alloy_primitives::Bytes;
+use kona_common::io;
+use kona_derive::traits::{LogLevel, TelemetryProvider};
+
+/// The [ClientTelemetry] writes the warnings and errors of the derivation pipeline to the standard
+/// error of the program, and drops the rest.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ClientTelemetry;
+
+impl TelemetryProvider for ClientTelemetry {
+    fn write<I: Into<Bytes>>(&self, data: I, level: LogLevel) {
+        if matches!(level, LogLevel::Warning | LogLevel::Error) {
+            io::print_err(&format!("{}\n", String::from_utf8_lossy(&data.into())));
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Contains the [PipeChannel], the host's end of a pair of OS pipes to the client program.
+
+use anyhow::{anyhow, Result};
+use kona_preimage::{Channel, ChannelClosed};
+use os_pipe::{PipeReader, PipeWriter};
+use std::io::{self, Read, Write};
+
+/// A [PipeChannel] is a [Channel] over a pair of OS pipes, one that is read from and one that is
+/// written to.
+#[derive(Debug)]
+pub(crate) struct PipeChannel {
+    /// The pipe that is read from.
+    reader: PipeReader,
+    /// The pipe that is written to.
+    writer: PipeWriter,
+}
+
+impl PipeChannel {
+    /// Create a new [PipeChannel] from the read end of one pipe and the write end of another.
+    pub(crate) fn new(reader: PipeReader, writer: PipeWriter) -> Self {
+        Self { reader, writer }
+    }
+}
+
+impl Channel for PipeChannel {
+    fn read_exact(&self, buf: &mut [u8]) -> Result<()> {
+        let mut read = 0;
+        while read < buf.len() {
+            match (&self.reader).read(&mut buf[read..]) {
+                Ok(0) => return Err(anyhow!(ChannelClosed { read, len: buf.len() })),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn write_all(&self, buf: &[u8]) -> Result<()> {
+        (&self.writer).write_all(buf)?;
+        (&self.writer).flush()?;
+        Ok(())
+    }
+}
+
+/// Returns `true` if the error was caused by the client program closing its end of a pipe or
+/// in-memory channel between frames. A channel closed partway through a frame is not a clean
+/// shutdown.
+pub(crate) fn is_closed(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<ChannelClosed>().is_some_and(|closed| closed.read == 0)
+}
@@ -0,0 +1,99 @@
+//! Contains the [HostCli] configuration of the host program.
+
+use anyhow::{anyhow, Result};
+use clap::{ArgAction, Parser};
+use kona_preimage::BootInfo;
+use std::{fs, path::PathBuf};
+use tracing::Level;
+
+/// The host binary CLI application arguments.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct HostCli {
+    /// Verbosity level (0-4)
+    #[arg(long, short, action = ArgAction::Count)]
+    pub v: u8,
+    /// Hash of the L1 head block. Derivation stops after this block is processed.
+    #[arg(long, value_parser = parse_b256)]
+    pub l1_head: [u8; 32],
+    /// Agreed upon L2 output root that the client program starts from.
+    #[arg(long, value_parser = parse_b256)]
+    pub l2_output_root: [u8; 32],
+    /// Disputed L2 output root claim.
+    #[arg(long, value_parser = parse_b256)]
+    pub l2_claim: [u8; 32],
+    /// L2 block number of the disputed claim.
+    #[arg(long)]
+    pub l2_block_number: u64,
+    /// The L2 chain ID.
+    #[arg(long)]
+    pub l2_chain_id: u64,
+    /// Path to the JSON-encoded L2 chain config.
+    #[arg(long)]
+    pub l2_chain_config: PathBuf,
+    /// Path to the JSON-encoded rollup config.
+    #[arg(long)]
+    pub rollup_config: PathBuf,
+    /// Directory of the preimage store, in the layout of `op-program`'s `--datadir`. If not set,
+    /// preimages are kept in memory.
+    #[arg(long, env = "KONA_HOST_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+    /// Path to the client program binary.
+    #[arg(long, required_unless_present = "native")]
+    pub exec: Option<PathBuf>,
+    /// Arguments passed through to the client program.
+    #[arg(last = true)]
+    pub exec_args: Vec<String>,
+    /// Run the client program natively on a thread of the host, rather than launching `exec`.
+    #[arg(long, conflicts_with = "exec")]
+    pub native: bool,
+}
+
+impl HostCli {
+    /// Assembles the [BootInfo] that the client program is served under its local keys.
+    pub fn boot_info(&self) -> Result<BootInfo> {
+        let l2_chain_config = fs::read(&self.l2_chain_config)
+            .map_err(|e| anyhow!("Failed to read L2 chain config: {e}"))?;
+        let rollup_config = fs::read(&self.rollup_config)
+            .map_err(|e| anyhow!("Failed to read rollup config: {e}"))?;
+
+        Ok(BootInfo {
+            l1_head: self.l1_head,
+            l2_output_root: self.l2_output_root,
+            l2_claim: self.l2_claim,
+            l2_claim_block_number: self.l2_block_number,
+            l2_chain_id: self.l2_chain_id,
+            l2_chain_config,
+            rollup_config,
+        })
+    }
+}
+
+/// Initializes the tracing subscriber
+///
+/// # Arguments
+/// * `verbosity_level` - The verbosity level (0-4)
+///
+/// # Returns
+/// * `Result<()>` - Ok if successful, Err otherwise.
+pub fn init_tracing_subscriber(verbosity_level: u8) -> Result<()> {
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(match verbosity_level {
+            0 => Level::ERROR,
+            1 => Level::WARN,
+            2 => Level::INFO,
+            3 => Level::DEBUG,
+            _ => Level::TRACE,
+        })
+        .finish();
+    tracing::subscriber::set_global_default(subscriber).map_err(|e| anyhow!(e))
+}
+
+/// Parses a 32-byte hash from a hex string, with an optional `0x` prefix.
+fn parse_b256(s: &str) -> Result<[u8; 32]> {
+    let mut hash = [0u8; 32];
+    hex::decode_to_slice(s.strip_prefix("0x").unwrap_or(s), &mut hash)
+        .map_err(|e| anyhow!("Invalid 32-byte hash {s}: {e}"))?;
+    Ok(hash)
+}
@@ -0,0 +1,13 @@
+//! The host program, which serves preimages to a client program. The client program is either
+//! launched as a child process that speaks to the host over pipes, or run natively on a thread of
+//! the host's process.
+
+#![warn(missing_debug_implementations, missing_docs, unreachable_pub, rustdoc::all)]
+#![deny(unused_must_use, rust_2018_idioms)]
+
+pub mod cli;
+pub mod native;
+pub mod process;
+pub mod server;
+
+mod channel;
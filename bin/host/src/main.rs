@@ -0,0 +1,45 @@
+//! The host program, which launches a client program and serves it preimages.
+
+#![warn(missing_debug_implementations, missing_docs, unreachable_pub, rustdoc::all)]
+#![deny(unused_must_use, rust_2018_idioms)]
+
+use anyhow::Result;
+use clap::Parser;
+use kona_host::{
+    cli::{init_tracing_subscriber, HostCli},
+    native, process,
+};
+use kona_preimage::{DiskKeyValueStore, KeyValueStore, MemoryKeyValueStore, SplitKeyValueStore};
+
+fn main() -> Result<()> {
+    let cfg = HostCli::parse();
+    init_tracing_subscriber(cfg.v)?;
+
+    // Local keys are served from the boot info, and all other keys from the preimage store.
+    let boot_info = cfg.boot_info()?;
+    let exit_code = match &cfg.data_dir {
+        Some(data_dir) => {
+            let store = SplitKeyValueStore::new(boot_info, DiskKeyValueStore::new(data_dir)?);
+            run(&cfg, store)?
+        }
+        None => {
+            let store = SplitKeyValueStore::new(boot_info, MemoryKeyValueStore::new());
+            run(&cfg, store)?
+        }
+    };
+
+    std::process::exit(exit_code)
+}
+
+/// Runs the client program over `store`, natively or as a child process, and returns its exit
+/// code.
+fn run<S>(cfg: &HostCli, store: S) -> Result<i32>
+where
+    S: KeyValueStore + Send + 'static,
+{
+    if cfg.native {
+        native::run_client(store)
+    } else {
+        process::run(cfg, store)
+    }
+}
@@ -0,0 +1,167 @@
+//! Contains the native execution mode of the host, which runs a client program on a thread of the
+//! host's process rather than in a child process.
+//!
+//! The client program is handed an [OracleReader] and [HintWriter] over in-memory
+//! [DuplexChannel]s, so it exercises the exact same code paths as when it runs on an FPVM, while
+//! remaining fast to run in tests and fully visible to a debugger.
+
+use crate::server::{start_hint_server, start_preimage_server};
+use anyhow::{anyhow, Result};
+use kona_client::StatelessExecutor;
+use kona_preimage::{DuplexChannel, HintWriter, KeyValueStore, OracleReader};
+use std::thread;
+use tracing::{error, info};
+
+/// The [OracleReader] handed to a natively executed client program.
+pub type NativeOracleReader = OracleReader<DuplexChannel>;
+
+/// The [HintWriter] handed to a natively executed client program.
+pub type NativeHintWriter = HintWriter<DuplexChannel>;
+
+/// Runs `client` natively on a new thread, serving its preimage requests from `store` and
+/// acknowledging its hints until it returns.
+///
+/// The client must return rather than exit the process, e.g. through `kona_common::io::exit`.
+///
+/// # Returns
+/// - `Ok(T)` with the value returned by the client.
+/// - `Err(_)` if the client panicked, or a server failed.
+pub fn run_native<S, F, T>(store: S, client: F) -> Result<T>
+where
+    S: KeyValueStore + Send + 'static,
+    F: FnOnce(NativeOracleReader, NativeHintWriter) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let (client_preimage, host_preimage) = DuplexChannel::pair();
+    let (client_hint, host_hint) = DuplexChannel::pair();
+
+    let servers = [start_preimage_server(host_preimage, store), start_hint_server(host_hint)];
+
+    // The client's channel ends are dropped when it returns, which stops the servers. If a server
+    // fails first, its channel end is dropped, and the client's pending read fails.
+    let client = thread::spawn(move || {
+        client(OracleReader::new(client_preimage), HintWriter::new(client_hint))
+    });
+    let output = client.join().map_err(|_| anyhow!("Client program panicked"))?;
+
+    for server in servers {
+        server.join().map_err(|_| anyhow!("Server thread panicked"))??;
+    }
+    Ok(output)
+}
+
+/// Runs the fault proof program natively with [run_native], serving its preimages from `store`.
+///
+/// # Returns
+/// - `Ok(exit_code)` with the exit code that the program exits with on an FPVM: `0` if the claim is
+///   valid, and `1` if it is invalid or could not be proven.
+/// - `Err(_)` if the program panicked, or a server failed.
+pub fn run_client<S>(store: S) -> Result<i32>
+where
+    S: KeyValueStore + Send + 'static,
+{
+    let verdict = run_native(store, |oracle, hint_writer| {
+        kona_client::check_claim(oracle, hint_writer, StatelessExecutor::new())
+    })?;
+
+    match verdict {
+        Ok(true) => {
+            info!(target: "host", "Claim is valid");
+            Ok(0)
+        }
+        Ok(false) => {
+            info!(target: "host", "Claim is invalid");
+            Ok(1)
+        }
+        Err(e) => {
+            error!(target: "host", "Failed to prove the claim: {e}");
+            Ok(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_consensus::Header;
+    use alloy_primitives::keccak256;
+    use kona_derive::types::RollupConfig;
+    use kona_preimage::{
+        BootInfo, HintWriterClient, MemoryKeyValueStore, PreimageKey, PreimageKeyType,
+        PreimageOracleClient, SplitKeyValueStore,
+    };
+
+    fn boot_info() -> BootInfo {
+        BootInfo {
+            l2_claim_block_number: 7,
+            l2_chain_id: 10,
+            l2_chain_config: b"{}".to_vec(),
+            rollup_config: b"{}".to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_run_native() {
+        let key = PreimageKey::new([0xAA; 32], PreimageKeyType::Keccak256);
+        let mut store = MemoryKeyValueStore::new();
+        store.set(key, b"preimage".to_vec()).unwrap();
+        let store = SplitKeyValueStore::new(boot_info(), store);
+
+        let (boot, preimage) = run_native(store, move |mut oracle, hint_writer| {
+            hint_writer.write("l1-block-header 0xabcd").unwrap();
+            let boot = BootInfo::load(&mut oracle).unwrap();
+            (boot, oracle.get(key).unwrap())
+        })
+        .unwrap();
+        assert_eq!(boot, boot_info());
+        assert_eq!(preimage, b"preimage");
+    }
+
+    /// Returns a store for a claim about the agreed upon L2 block, which is valid if `claim` is
+    /// the agreed upon output root.
+    fn claim_store(claim: Option<[u8; 32]>) -> impl KeyValueStore + Send + 'static {
+        let header = Header { number: 7, ..Default::default() };
+        let block_hash = header.hash_slow();
+        let mut output = [0u8; 128];
+        output[96..].copy_from_slice(block_hash.as_slice());
+        let output_root = keccak256(output);
+
+        let rollup_config = RollupConfig { l2_chain_id: 10, ..Default::default() };
+        let boot_info = BootInfo {
+            l2_output_root: *output_root,
+            l2_claim: claim.unwrap_or(*output_root),
+            rollup_config: serde_json::to_vec(&rollup_config).unwrap(),
+            ..boot_info()
+        };
+
+        let mut store = MemoryKeyValueStore::new();
+        let preimages = [(output_root, output.to_vec()), (block_hash, alloy_rlp::encode(&header))];
+        for (hash, preimage) in preimages {
+            store.set(PreimageKey::new(*hash, PreimageKeyType::Keccak256), preimage).unwrap();
+        }
+        SplitKeyValueStore::new(boot_info, store)
+    }
+
+    #[test]
+    fn test_run_client() {
+        assert_eq!(run_client(claim_store(None)).unwrap(), 0);
+        assert_eq!(run_client(claim_store(Some([1; 32]))).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_run_client_unproven() {
+        // The rollup config cannot be parsed, so the claim cannot be proven.
+        let store = SplitKeyValueStore::new(boot_info(), MemoryKeyValueStore::new());
+        assert_eq!(run_client(store).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_run_native_missing_preimage() {
+        let key = PreimageKey::new([0xAA; 32], PreimageKeyType::Keccak256);
+        let result = run_native(MemoryKeyValueStore::new(), move |mut oracle, _| {
+            assert!(oracle.get(key).is_err());
+        });
+        assert!(result.is_err());
+    }
+}
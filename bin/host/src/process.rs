@@ -0,0 +1,126 @@
+//! Contains the logic for launching the client program as a child process, wired to the host
+//! through the hint and preimage pipes.
+
+use crate::{
+    channel::PipeChannel,
+    cli::HostCli,
+    server::{start_hint_server, start_preimage_server},
+};
+use anyhow::{anyhow, bail, Result};
+use kona_common::{FileDescriptor, RegisterSize};
+use kona_preimage::KeyValueStore;
+use std::{
+    io,
+    os::{
+        fd::{AsRawFd, RawFd},
+        unix::process::CommandExt,
+    },
+    process::Command,
+    thread,
+    time::Duration,
+};
+use tracing::{error, info};
+
+/// The interval at which the client program and the servers are polled for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Launches the client program, serves its preimage requests from `store` and acknowledges its
+/// hints until it exits. The client inherits the host's stdout and stderr.
+///
+/// # Returns
+/// - `Ok(exit_code)` with the exit code of the client program.
+/// - `Err(_)` if no client program was configured, it could not be launched, or a server failed.
+pub fn run<S>(cfg: &HostCli, store: S) -> Result<i32>
+where
+    S: KeyValueStore + Send + 'static,
+{
+    let exec = cfg.exec.as_ref().ok_or_else(|| anyhow!("No client program to launch"))?;
+
+    // The hint and preimage protocols each run over a pair of unidirectional pipes.
+    let (host_hint_read, client_hint_write) = os_pipe::pipe()?;
+    let (client_hint_read, host_hint_write) = os_pipe::pipe()?;
+    let (host_preimage_read, client_preimage_write) = os_pipe::pipe()?;
+    let (client_preimage_read, host_preimage_write) = os_pipe::pipe()?;
+
+    let mappings = [
+        (client_hint_read.as_raw_fd(), FileDescriptor::HintRead),
+        (client_hint_write.as_raw_fd(), FileDescriptor::HintWrite),
+        (client_preimage_read.as_raw_fd(), FileDescriptor::PreimageRead),
+        (client_preimage_write.as_raw_fd(), FileDescriptor::PreimageWrite),
+    ]
+    .map(|(parent_fd, fd)| (parent_fd, RegisterSize::from(fd) as RawFd));
+
+    let mut command = Command::new(exec);
+    command.args(&cfg.exec_args);
+    // SAFETY: The closure only makes async-signal-safe system calls and does not allocate.
+    unsafe { command.pre_exec(move || map_fds(&mappings)) };
+    let mut child =
+        command.spawn().map_err(|e| anyhow!("Failed to spawn {}: {e}", exec.display()))?;
+    info!(target: "host", "Launched client program {} (pid {})", exec.display(), child.id());
+
+    // Close the host's copies of the client's pipe ends, so that the servers observe the pipes
+    // closing once the client exits.
+    drop((client_hint_read, client_hint_write, client_preimage_read, client_preimage_write));
+
+    let mut servers = vec![
+        start_preimage_server(PipeChannel::new(host_preimage_read, host_preimage_write), store),
+        start_hint_server(PipeChannel::new(host_hint_read, host_hint_write)),
+    ];
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        // If a server failed, the client would block forever waiting on it.
+        if let Some(i) = servers.iter().position(|s| s.is_finished()) {
+            if let Err(e) = join(servers.swap_remove(i)) {
+                error!(target: "host", "Server failed, killing client program: {e}");
+                child.kill()?;
+                child.wait()?;
+                return Err(e);
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    for server in servers {
+        join(server)?;
+    }
+
+    info!(target: "host", "Client program exited with {status}");
+    match status.code() {
+        Some(code) => Ok(code),
+        None => bail!("Client program was terminated by a signal: {status}"),
+    }
+}
+
+/// Maps each parent file descriptor to its child file descriptor. Runs in the child process,
+/// between `fork` and `exec`.
+fn map_fds(mappings: &[(RawFd, RawFd); 4]) -> io::Result<()> {
+    // Move the parent file descriptors above the range of child file descriptors first, so that
+    // mapping one does not overwrite another that has not been mapped yet.
+    let mut sources = [0; 4];
+    for (source, (parent_fd, _)) in sources.iter_mut().zip(mappings) {
+        // SAFETY: `fcntl` with `F_DUPFD_CLOEXEC` only duplicates a file descriptor.
+        *source = unsafe { libc::fcntl(*parent_fd, libc::F_DUPFD_CLOEXEC, 7) };
+        if *source < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    // `dup2` clears `FD_CLOEXEC` on the duplicate, so the child file descriptors survive `exec`.
+    for (source, (_, child_fd)) in sources.iter().zip(mappings) {
+        // SAFETY: `dup2` only duplicates a file descriptor.
+        if unsafe { libc::dup2(*source, *child_fd) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Joins a server thread, surfacing a panic as an error.
+fn join(server: thread::JoinHandle<Result<()>>) -> Result<()> {
+    server.join().map_err(|_| anyhow!("Server thread panicked"))?
+}
@@ -0,0 +1,107 @@
+//! Contains the servers that answer the client program's preimage requests and hints.
+
+use crate::channel::is_closed;
+use anyhow::Result;
+use kona_preimage::{
+    Channel, Hint, HintReader, HintReaderServer, HintRouter, KeyValueFetcher, KeyValueStore,
+    OracleServer, PreimageOracleServer,
+};
+use std::thread::{self, JoinHandle};
+use tracing::{debug, warn};
+
+/// Spawns a thread that serves the client program's preimage requests from `store`, until the
+/// client closes its end of the channel.
+pub fn start_preimage_server<C, S>(channel: C, store: S) -> JoinHandle<Result<()>>
+where
+    C: Channel + Send + 'static,
+    S: KeyValueStore + Send + 'static,
+{
+    thread::spawn(move || {
+        let server = OracleServer::new(channel);
+        let mut fetcher = KeyValueFetcher::new(store);
+        serve(|| server.next_preimage_request(&mut fetcher))
+    })
+}
+
+/// Spawns a thread that acknowledges the client program's hints, until the client closes its end
+/// of the channel.
+pub fn start_hint_server<C>(channel: C) -> JoinHandle<Result<()>>
+where
+    C: Channel + Send + 'static,
+{
+    thread::spawn(move || {
+        let server = HintReader::new(channel);
+        serve(|| server.next_hint(&mut HintLogger))
+    })
+}
+
+/// Runs `next` until the client closes its end of the channel, or an error occurs.
+fn serve<F: FnMut() -> Result<()>>(mut next: F) -> Result<()> {
+    loop {
+        match next() {
+            Ok(()) => {}
+            Err(e) if is_closed(&e) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A [HintRouter] that logs hints. The host serves preimages from a prepared store, so hints
+/// need no further handling.
+#[derive(Debug)]
+struct HintLogger;
+
+impl HintRouter for HintLogger {
+    fn route_hint(&mut self, hint: &str) -> Result<()> {
+        match Hint::parse(hint) {
+            Ok(hint) => debug!(target: "host", "Received hint: {}", hint.encode()),
+            Err(e) => warn!(target: "host", "Received invalid hint {hint}: {e}"),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::channel::PipeChannel;
+    use kona_preimage::MemoryKeyValueStore;
+    use std::io::Write;
+
+    /// Returns the host's [PipeChannel], and the client's end of the pipe it reads from.
+    fn host_channel() -> (PipeChannel, os_pipe::PipeWriter) {
+        let (host_reader, client_writer) = os_pipe::pipe().unwrap();
+        let (_, host_writer) = os_pipe::pipe().unwrap();
+        (PipeChannel::new(host_reader, host_writer), client_writer)
+    }
+
+    #[test]
+    fn test_preimage_server_client_closed() {
+        // A client that closes the channel between requests shuts the server down cleanly.
+        let (channel, client) = host_channel();
+        drop(client);
+        let server = start_preimage_server(channel, MemoryKeyValueStore::new());
+        server.join().unwrap().unwrap();
+
+        // A client that closes the channel partway through a key is an error.
+        let (channel, mut client) = host_channel();
+        client.write_all(&[0x02; 16]).unwrap();
+        drop(client);
+        let server = start_preimage_server(channel, MemoryKeyValueStore::new());
+        assert!(server.join().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_hint_server_client_closed() {
+        let (channel, client) = host_channel();
+        drop(client);
+        start_hint_server(channel).join().unwrap().unwrap();
+
+        // A hint shorter than its length prefix is an error.
+        let (channel, mut client) = host_channel();
+        client.write_all(&8u32.to_be_bytes()).unwrap();
+        client.write_all(b"l2-").unwrap();
+        drop(client);
+        assert!(start_hint_server(channel).join().unwrap().is_err());
+    }
+}
@@ -78,9 +78,13 @@ mod native_io {
                 .write(buf)
                 .map_err(|e| anyhow!("Error writing to buffer to file descriptor: {e}"))?;
 
-            // Reset the cursor back to 0 for the reader.
-            file.seek(SeekFrom::Start(0))
-                .map_err(|e| anyhow!("Failed to reset file cursor to 0: {e}"))?;
+            // Reset the cursor back to 0 for the reader. Only regular files have a cursor; pipes
+            // to the host do not.
+            let is_file = file.metadata().map(|m| m.is_file()).unwrap_or_default();
+            if is_file {
+                file.seek(SeekFrom::Start(0))
+                    .map_err(|e| anyhow!("Failed to reset file cursor to 0: {e}"))?;
+            }
 
             // forget the file descriptor so that the `Drop` impl doesn't close it.
             std::mem::forget(file);
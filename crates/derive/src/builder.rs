@@ -0,0 +1,213 @@
+//! Contains the [PipelineBuilder], which assembles a [DerivationPipeline] from its typed
+//! configuration.
+
+use crate::{
+    stages::{
+        AttributesBuilder, AttributesQueue, BatchQueue, ChannelBank, ChannelReader, FrameQueue,
+        L1Retrieval, L1Traversal,
+    },
+    traits::{ChainProvider, DataAvailabilityProvider, SafeBlockFetcher, TelemetryProvider},
+    types::RollupConfig,
+    DerivationPipeline,
+};
+use alloc::sync::Arc;
+use anyhow::{anyhow, Result};
+use core::fmt::Debug;
+
+/// The [DerivationPipeline] produced by a [PipelineBuilder]. A single telemetry provider is shared
+/// by every stage of the pipeline.
+pub type BuiltPipeline<CP, DAP, BF, AB, T> = DerivationPipeline<CP, DAP, BF, AB, Arc<T>>;
+
+/// The [PipelineBuilder] assembles the stages of a [DerivationPipeline] from a rollup config and
+/// the data sources and providers that the stages depend on.
+///
+/// The stages take their shared configuration in different forms, e.g. an [`Arc<RollupConfig>`]
+/// or a [RollupConfig]. The builder hides these differences.
+#[derive(Debug)]
+pub struct PipelineBuilder<CP, DAP, BF, AB, T>
+where
+    CP: ChainProvider + Send + Debug,
+    DAP: DataAvailabilityProvider + Send + Debug,
+    BF: SafeBlockFetcher + Send + Debug,
+    AB: AttributesBuilder + Send + Debug,
+    T: TelemetryProvider + Send + Sync + Debug,
+{
+    /// The rollup config.
+    rollup_config: Option<Arc<RollupConfig>>,
+    /// The L1 chain provider.
+    chain_provider: Option<CP>,
+    /// The L2 safe block fetcher.
+    l2_block_fetcher: Option<BF>,
+    /// The data availability provider.
+    dap_source: Option<DAP>,
+    /// The payload attributes builder.
+    attributes_builder: Option<AB>,
+    /// The telemetry provider.
+    telemetry: Option<T>,
+}
+
+impl<CP, DAP, BF, AB, T> Default for PipelineBuilder<CP, DAP, BF, AB, T>
+where
+    CP: ChainProvider + Send + Debug,
+    DAP: DataAvailabilityProvider + Send + Debug,
+    BF: SafeBlockFetcher + Send + Debug,
+    AB: AttributesBuilder + Send + Debug,
+    T: TelemetryProvider + Send + Sync + Debug,
+{
+    fn default() -> Self {
+        Self {
+            rollup_config: None,
+            chain_provider: None,
+            l2_block_fetcher: None,
+            dap_source: None,
+            attributes_builder: None,
+            telemetry: None,
+        }
+    }
+}
+
+impl<CP, DAP, BF, AB, T> PipelineBuilder<CP, DAP, BF, AB, T>
+where
+    CP: ChainProvider + Send + Debug,
+    DAP: DataAvailabilityProvider + Send + Debug,
+    BF: SafeBlockFetcher + Send + Debug,
+    AB: AttributesBuilder + Send + Debug,
+    T: TelemetryProvider + Send + Sync + Debug,
+{
+    /// Creates a new, empty [PipelineBuilder].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the rollup config, either owned or shared.
+    pub fn rollup_config(mut self, rollup_config: impl Into<Arc<RollupConfig>>) -> Self {
+        self.rollup_config = Some(rollup_config.into());
+        self
+    }
+
+    /// Sets the L1 chain provider.
+    pub fn chain_provider(mut self, chain_provider: CP) -> Self {
+        self.chain_provider = Some(chain_provider);
+        self
+    }
+
+    /// Sets the L2 safe block fetcher.
+    pub fn l2_block_fetcher(mut self, l2_block_fetcher: BF) -> Self {
+        self.l2_block_fetcher = Some(l2_block_fetcher);
+        self
+    }
+
+    /// Sets the data availability provider.
+    pub fn dap_source(mut self, dap_source: DAP) -> Self {
+        self.dap_source = Some(dap_source);
+        self
+    }
+
+    /// Sets the payload attributes builder.
+    pub fn attributes_builder(mut self, attributes_builder: AB) -> Self {
+        self.attributes_builder = Some(attributes_builder);
+        self
+    }
+
+    /// Sets the telemetry provider, which is shared by every stage of the pipeline.
+    pub fn telemetry(mut self, telemetry: T) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Builds the [DerivationPipeline].
+    ///
+    /// # Returns
+    /// - `Ok(BuiltPipeline)` with the assembled pipeline.
+    /// - `Err(_)` if any part of the configuration was not set.
+    pub fn build(self) -> Result<BuiltPipeline<CP, DAP, BF, AB, T>> {
+        let rollup_config = self.rollup_config.ok_or_else(|| missing("rollup config"))?;
+        let chain_provider = self.chain_provider.ok_or_else(|| missing("chain provider"))?;
+        let l2_block_fetcher = self.l2_block_fetcher.ok_or_else(|| missing("L2 block fetcher"))?;
+        let dap_source = self.dap_source.ok_or_else(|| missing("data availability provider"))?;
+        let attributes_builder =
+            self.attributes_builder.ok_or_else(|| missing("attributes builder"))?;
+        let telemetry = Arc::new(self.telemetry.ok_or_else(|| missing("telemetry provider"))?);
+
+        let l1_traversal =
+            L1Traversal::new(chain_provider, rollup_config.clone(), telemetry.clone());
+        let l1_retrieval = L1Retrieval::new(l1_traversal, dap_source, telemetry.clone());
+        let frame_queue = FrameQueue::new(l1_retrieval, telemetry.clone());
+        let channel_bank = ChannelBank::new(rollup_config.clone(), frame_queue, telemetry.clone());
+        let channel_reader = ChannelReader::new(channel_bank, telemetry.clone());
+        let batch_queue =
+            BatchQueue::new(*rollup_config, channel_reader, telemetry.clone(), l2_block_fetcher);
+        let attributes_queue =
+            AttributesQueue::new(*rollup_config, batch_queue, telemetry, attributes_builder);
+
+        Ok(DerivationPipeline::new(attributes_queue))
+    }
+}
+
+/// Returns the error for a part of the configuration that was not set.
+fn missing(part: &str) -> anyhow::Error {
+    anyhow!("Missing {part} for the derivation pipeline")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        stages::test_utils::MockAttributesBuilder,
+        traits::{
+            test_utils::{MockBlockFetcher, TestChainProvider, TestDAP, TestTelemetry},
+            OriginProvider,
+        },
+        types::{BlockID, BlockInfo, L2BlockInfo, StageError, SystemConfig},
+    };
+    use alloc::vec::Vec;
+    use alloy_primitives::B256;
+
+    #[test]
+    fn test_builder_missing_config() {
+        let builder: PipelineBuilder<
+            TestChainProvider,
+            TestDAP,
+            MockBlockFetcher,
+            MockAttributesBuilder,
+            TestTelemetry,
+        > = PipelineBuilder::new().chain_provider(TestChainProvider::default());
+        assert!(builder.build().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_builder_full_config_steps() {
+        let blocks: Vec<BlockInfo> = (0..2)
+            .map(|i| BlockInfo {
+                hash: B256::with_last_byte(i as u8 + 1),
+                number: i,
+                parent_hash: if i == 0 { B256::ZERO } else { B256::with_last_byte(i as u8) },
+                timestamp: i * 12,
+            })
+            .collect();
+        let mut provider = TestChainProvider::default();
+        for block in &blocks {
+            provider.insert_block(block.number, *block);
+            provider.insert_receipts(block.hash, Vec::new());
+        }
+
+        let mut pipeline = PipelineBuilder::new()
+            .rollup_config(RollupConfig { seq_window_size: 100, ..RollupConfig::default() })
+            .chain_provider(provider)
+            .l2_block_fetcher(MockBlockFetcher::default())
+            .dap_source(TestDAP::default())
+            .attributes_builder(MockAttributesBuilder::default())
+            .telemetry(TestTelemetry::new())
+            .build()
+            .unwrap();
+        pipeline.reset(blocks[0], SystemConfig::default()).await.unwrap();
+
+        // Without any batch data, the step runs through every stage, advances to the last L1
+        // block, and fails once there is no next L1 block.
+        let l1_origin = BlockID { hash: blocks[0].hash, number: blocks[0].number };
+        let safe_head = L2BlockInfo { l1_origin, ..Default::default() };
+        let err = pipeline.step(safe_head).await.unwrap_err();
+        assert!(matches!(err, StageError::BlockInfoFetch(_)), "{err:?}");
+        assert_eq!(pipeline.origin(), Some(&blocks[1]));
+    }
+}
@@ -9,15 +9,20 @@ extern crate alloc;
 mod params;
 pub use params::{
     ChannelID, CHANNEL_ID_LENGTH, CONFIG_UPDATE_EVENT_VERSION_0, CONFIG_UPDATE_TOPIC,
-    DERIVATION_VERSION_0, FRAME_OVERHEAD, MAX_CHANNEL_BANK_SIZE, MAX_FRAME_LEN,
-    MAX_RLP_BYTES_PER_CHANNEL, MAX_SPAN_BATCH_BYTES,
+    DEPOSIT_EVENT_ABI_HASH, DERIVATION_VERSION_0, FRAME_OVERHEAD, MAX_CHANNEL_BANK_SIZE,
+    MAX_FRAME_LEN, MAX_RLP_BYTES_PER_CHANNEL, MAX_SPAN_BATCH_BYTES,
+};
+
+mod builder;
+pub use builder::{BuiltPipeline, PipelineBuilder};
+
+mod pipeline;
+pub use pipeline::{
+    AttributesQueueStage, BatchQueueStage, ChannelBankStage, ChannelReaderStage,
+    DerivationPipeline, FrameQueueStage, L1RetrievalStage, L1TraversalStage,
 };
 
 pub mod sources;
 pub mod stages;
 pub mod traits;
 pub mod types;
-
-/// The derivation pipeline is responsible for deriving L2 inputs from L1 data.
-#[derive(Debug, Clone, Copy)]
-pub struct DerivationPipeline;
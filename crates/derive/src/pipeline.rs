@@ -0,0 +1,219 @@
+//! Contains the [DerivationPipeline], which owns the full stack of derivation stages and drives
+//! them to produce payload attributes.
+
+use crate::{
+    stages::{
+        AttributesBuilder, AttributesQueue, BatchQueue, ChannelBank, ChannelReader, FrameQueue,
+        L1Retrieval, L1Traversal,
+    },
+    traits::{
+        ChainProvider, DataAvailabilityProvider, OriginProvider, ResettableStage, SafeBlockFetcher,
+        TelemetryProvider,
+    },
+    types::{AttributesWithParent, BlockInfo, L2BlockInfo, StageError, StageResult, SystemConfig},
+};
+use core::fmt::Debug;
+
+/// The [L1Traversal] stage of a [DerivationPipeline].
+pub type L1TraversalStage<CP, T> = L1Traversal<CP, T>;
+
+/// The [L1Retrieval] stage of a [DerivationPipeline].
+pub type L1RetrievalStage<CP, DAP, T> = L1Retrieval<DAP, L1TraversalStage<CP, T>, T>;
+
+/// The [FrameQueue] stage of a [DerivationPipeline].
+pub type FrameQueueStage<CP, DAP, T> = FrameQueue<L1RetrievalStage<CP, DAP, T>, T>;
+
+/// The [ChannelBank] stage of a [DerivationPipeline].
+pub type ChannelBankStage<CP, DAP, T> = ChannelBank<FrameQueueStage<CP, DAP, T>, T>;
+
+/// The [ChannelReader] stage of a [DerivationPipeline].
+pub type ChannelReaderStage<CP, DAP, T> = ChannelReader<ChannelBankStage<CP, DAP, T>, T>;
+
+/// The [BatchQueue] stage of a [DerivationPipeline].
+pub type BatchQueueStage<CP, DAP, BF, T> = BatchQueue<ChannelReaderStage<CP, DAP, T>, BF, T>;
+
+/// The [AttributesQueue] stage of a [DerivationPipeline], the top of the stage stack.
+pub type AttributesQueueStage<CP, DAP, BF, AB, T> =
+    AttributesQueue<BatchQueueStage<CP, DAP, BF, T>, T, AB>;
+
+/// The derivation pipeline is responsible for deriving L2 inputs from L1 data.
+///
+/// It owns the full stack of stages, from [L1Traversal] up to [AttributesQueue], and exposes a
+/// [DerivationPipeline::step] driver that produces the next [AttributesWithParent] on top of the
+/// L2 safe head, advancing the L1 origin of the stack as the stages exhaust their data.
+#[derive(Debug)]
+pub struct DerivationPipeline<CP, DAP, BF, AB, T>
+where
+    CP: ChainProvider + Send + Debug,
+    DAP: DataAvailabilityProvider + Send + Debug,
+    BF: SafeBlockFetcher + Send + Debug,
+    AB: AttributesBuilder + Send + Debug,
+    T: TelemetryProvider + Send + Sync + Debug,
+{
+    /// The top of the stage stack.
+    attributes: AttributesQueueStage<CP, DAP, BF, AB, T>,
+}
+
+impl<CP, DAP, BF, AB, T> DerivationPipeline<CP, DAP, BF, AB, T>
+where
+    CP: ChainProvider + Send + Debug,
+    DAP: DataAvailabilityProvider + Send + Debug,
+    BF: SafeBlockFetcher + Send + Debug,
+    AB: AttributesBuilder + Send + Debug,
+    T: TelemetryProvider + Send + Sync + Debug,
+{
+    /// Creates a new [DerivationPipeline] from the top of an assembled stage stack.
+    pub fn new(attributes: AttributesQueueStage<CP, DAP, BF, AB, T>) -> Self {
+        Self { attributes }
+    }
+
+    /// Derives the next [AttributesWithParent] on top of the given L2 safe head.
+    ///
+    /// When the stages exhaust the data of the current L1 origin ([StageError::Eof]), the
+    /// [L1Traversal] stage is advanced to the next L1 block. When a stage made progress but has
+    /// nothing to return yet ([StageError::NotEnoughData]), the stack is stepped again.
+    ///
+    /// # Returns
+    /// - `Ok(AttributesWithParent)` with the next payload attributes.
+    /// - `Err(_)` if a stage failed, or the L1 origin could not be advanced.
+    pub async fn step(&mut self, safe_head: L2BlockInfo) -> StageResult<AttributesWithParent> {
+        loop {
+            match self.attributes.next_attributes(safe_head).await {
+                Err(StageError::Eof) => self.traversal_mut().advance_l1_block().await?,
+                Err(StageError::NotEnoughData) => continue,
+                result => return result,
+            }
+        }
+    }
+
+    /// Resets every stage of the pipeline, from the bottom of the stack up, to derive from the
+    /// given L1 origin with the given [SystemConfig].
+    ///
+    /// # Returns
+    /// - `Ok(())` if every stage was reset.
+    /// - `Err(_)` if a stage failed to reset.
+    pub async fn reset(
+        &mut self,
+        l1_origin: BlockInfo,
+        system_config: SystemConfig,
+    ) -> StageResult<()> {
+        let batch_queue = &mut self.attributes.prev;
+        let channel_reader = &mut batch_queue.prev;
+        let channel_bank = &mut channel_reader.prev;
+        let frame_queue = &mut channel_bank.prev;
+        let l1_retrieval = &mut frame_queue.prev;
+
+        reset_stage(&mut l1_retrieval.prev, l1_origin, system_config).await?;
+        reset_stage(l1_retrieval, l1_origin, system_config).await?;
+        reset_stage(frame_queue, l1_origin, system_config).await?;
+        reset_stage(channel_bank, l1_origin, system_config).await?;
+        reset_stage(channel_reader, l1_origin, system_config).await?;
+        reset_stage(batch_queue, l1_origin, system_config).await?;
+        reset_stage(&mut self.attributes, l1_origin, system_config).await
+    }
+
+    /// Returns a mutable reference to the [L1Traversal] stage at the bottom of the stack.
+    fn traversal_mut(&mut self) -> &mut L1TraversalStage<CP, T> {
+        &mut self.attributes.prev.prev.prev.prev.prev.prev
+    }
+}
+
+impl<CP, DAP, BF, AB, T> OriginProvider for DerivationPipeline<CP, DAP, BF, AB, T>
+where
+    CP: ChainProvider + Send + Debug,
+    DAP: DataAvailabilityProvider + Send + Debug,
+    BF: SafeBlockFetcher + Send + Debug,
+    AB: AttributesBuilder + Send + Debug,
+    T: TelemetryProvider + Send + Sync + Debug,
+{
+    fn origin(&self) -> Option<&BlockInfo> {
+        self.attributes.origin()
+    }
+}
+
+/// Resets a single stage. Stages signal a completed reset with [StageError::Eof], which is not
+/// an error for the pipeline.
+async fn reset_stage<S: ResettableStage>(
+    stage: &mut S,
+    l1_origin: BlockInfo,
+    system_config: SystemConfig,
+) -> StageResult<()> {
+    match stage.reset(l1_origin, system_config).await {
+        Ok(()) | Err(StageError::Eof) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        stages::test_utils::MockAttributesBuilder,
+        traits::test_utils::{MockBlockFetcher, TestChainProvider, TestDAP, TestTelemetry},
+        types::{BlockID, RollupConfig},
+        BuiltPipeline, PipelineBuilder,
+    };
+    use alloc::vec::Vec;
+    use alloy_primitives::B256;
+
+    type TestPipeline = BuiltPipeline<
+        TestChainProvider,
+        TestDAP,
+        MockBlockFetcher,
+        MockAttributesBuilder,
+        TestTelemetry,
+    >;
+
+    /// Returns a chain of `count` L1 blocks, linked by their parent hashes.
+    fn new_l1_chain(count: u64) -> Vec<BlockInfo> {
+        (0..count)
+            .map(|i| BlockInfo {
+                hash: B256::with_last_byte(i as u8 + 1),
+                number: i,
+                parent_hash: if i == 0 { B256::ZERO } else { B256::with_last_byte(i as u8) },
+                timestamp: i * 12,
+            })
+            .collect()
+    }
+
+    fn new_test_pipeline(blocks: &[BlockInfo]) -> TestPipeline {
+        let mut provider = TestChainProvider::default();
+        for block in blocks {
+            provider.insert_block(block.number, *block);
+            provider.insert_receipts(block.hash, Vec::new());
+        }
+
+        PipelineBuilder::new()
+            .rollup_config(RollupConfig { seq_window_size: 100, ..RollupConfig::default() })
+            .chain_provider(provider)
+            .l2_block_fetcher(MockBlockFetcher::default())
+            .dap_source(TestDAP::default())
+            .attributes_builder(MockAttributesBuilder::default())
+            .telemetry(TestTelemetry::new())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_reset() {
+        let blocks = new_l1_chain(2);
+        let mut pipeline = new_test_pipeline(&blocks);
+        pipeline.reset(blocks[1], SystemConfig::default()).await.unwrap();
+        assert_eq!(pipeline.origin(), Some(&blocks[1]));
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_step_advances_origin() {
+        let blocks = new_l1_chain(3);
+        let mut pipeline = new_test_pipeline(&blocks);
+        pipeline.reset(blocks[0], SystemConfig::default()).await.unwrap();
+
+        // Without any batch data, the pipeline advances through every L1 block, and fails once
+        // there is no next L1 block to advance to.
+        let l1_origin = BlockID { hash: blocks[0].hash, number: blocks[0].number };
+        let safe_head = L2BlockInfo { l1_origin, ..Default::default() };
+        let err = pipeline.step(safe_head).await.unwrap_err();
+        assert!(matches!(err, StageError::BlockInfoFetch(_)), "{err:?}");
+        assert_eq!(pipeline.origin(), Some(&blocks[2]));
+    }
+}
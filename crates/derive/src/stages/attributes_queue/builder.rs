@@ -0,0 +1,396 @@
+//! Contains the [AttributesBuilder] trait, and the [StatefulAttributesBuilder] that implements it
+//! on top of an L1 [ChainProvider].
+
+use crate::{
+    traits::ChainProvider,
+    types::{
+        derive_deposits, ecotone_upgrade_transactions, BlockID, L1BlockInfoTx, L2BlockInfo,
+        PayloadAttributes, RollupConfig, SystemAccounts, SystemConfig, Withdrawals,
+    },
+};
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use core::fmt::Debug;
+
+/// [AttributesBuilder] is a trait abstraction that builds the [PayloadAttributes] for the next L2
+/// block on top of its parent, within the given L1 epoch.
+#[async_trait]
+pub trait AttributesBuilder {
+    /// Prepare the payload attributes.
+    async fn prepare_payload_attributes(
+        &mut self,
+        l2_parent: L2BlockInfo,
+        epoch: BlockID,
+    ) -> anyhow::Result<PayloadAttributes>;
+
+    /// Resets any state that the builder has derived, such that the next L2 parent is built upon
+    /// with the given [SystemConfig]. Builders without state need not implement this.
+    fn reset(&mut self, _system_config: SystemConfig) {}
+}
+
+/// The [StatefulAttributesBuilder] builds the [PayloadAttributes] of an L2 block as specified by
+/// the OP Stack derivation spec, i.e. with the L1 info deposit, the user deposits of a new epoch,
+/// and the network upgrade transactions of a hardfork activation block, which are forced into the
+/// block ahead of the batched transactions.
+///
+/// The builder tracks the [SystemConfig] of the L2 chain, applying the config updates of each new
+/// epoch as it is entered. It must be created, and reset, with the [SystemConfig] of the L2 parent
+/// that it will next build upon.
+#[derive(Debug)]
+pub struct StatefulAttributesBuilder<CP>
+where
+    CP: ChainProvider + Debug,
+{
+    /// The rollup config.
+    rollup_config: Arc<RollupConfig>,
+    /// The system config of the last L2 parent that was built upon.
+    system_config: SystemConfig,
+    /// The L1 chain provider, for the headers and receipts of epochs.
+    chain_provider: CP,
+}
+
+impl<CP> StatefulAttributesBuilder<CP>
+where
+    CP: ChainProvider + Debug,
+{
+    /// Creates a new [StatefulAttributesBuilder] that builds upon an L2 parent with the given
+    /// [SystemConfig].
+    pub fn new(
+        rollup_config: Arc<RollupConfig>,
+        system_config: SystemConfig,
+        chain_provider: CP,
+    ) -> Self {
+        Self { rollup_config, system_config, chain_provider }
+    }
+
+    /// Returns the [SystemConfig] of the last L2 parent that was built upon.
+    pub fn system_config(&self) -> &SystemConfig {
+        &self.system_config
+    }
+}
+
+#[async_trait]
+impl<CP> AttributesBuilder for StatefulAttributesBuilder<CP>
+where
+    CP: ChainProvider + Send + Sync + Debug,
+{
+    async fn prepare_payload_attributes(
+        &mut self,
+        l2_parent: L2BlockInfo,
+        epoch: BlockID,
+    ) -> anyhow::Result<PayloadAttributes> {
+        let mut system_config = self.system_config;
+
+        // If the L1 origin changed in this block, then we are in the first block of the epoch. In
+        // this case we need to fetch all deposits and system config updates of the new epoch.
+        let (l1_header, deposits, sequence_number) = if l2_parent.l1_origin.number != epoch.number {
+            let header = self.chain_provider.header_by_hash(epoch.hash).await?;
+            if l2_parent.l1_origin.hash != header.parent_hash {
+                bail!(
+                    "Parent L2 block L1 origin {} does not match the parent of the next epoch {}",
+                    l2_parent.l1_origin,
+                    epoch
+                );
+            }
+
+            let receipts = self.chain_provider.receipts_by_hash(epoch.hash).await?;
+            system_config.update_with_receipts(&receipts, &self.rollup_config, header.timestamp)?;
+            let deposits = derive_deposits(
+                epoch.hash,
+                &receipts,
+                self.rollup_config.deposit_contract_address,
+            )?;
+            (header, deposits, 0)
+        } else {
+            if l2_parent.l1_origin.hash != epoch.hash {
+                bail!(
+                    "Parent L2 block L1 origin {} does not match the epoch {}",
+                    l2_parent.l1_origin,
+                    epoch
+                );
+            }
+
+            let header = self.chain_provider.header_by_hash(epoch.hash).await?;
+            (header, Vec::new(), l2_parent.seq_num + 1)
+        };
+
+        // Sanity check the L1 origin was correctly selected to maintain the time invariant between
+        // L1 and L2.
+        let next_l2_time = l2_parent.block_info.timestamp + self.rollup_config.block_time;
+        if next_l2_time < l1_header.timestamp {
+            bail!(
+                "Cannot build L2 block on top of {} for time {} before L1 origin {} at time {}",
+                l2_parent.block_info,
+                next_l2_time,
+                epoch,
+                l1_header.timestamp
+            );
+        }
+
+        let (_, l1_info_tx) = L1BlockInfoTx::try_new_with_deposit_tx(
+            &self.rollup_config,
+            &system_config,
+            sequence_number,
+            &l1_header,
+            epoch.hash,
+            next_l2_time,
+        )?;
+
+        let mut transactions = vec![l1_info_tx.into()];
+        transactions.extend(deposits);
+        if self.rollup_config.is_ecotone_activation_block(next_l2_time) {
+            transactions.extend(ecotone_upgrade_transactions()?);
+        }
+
+        let withdrawals =
+            self.rollup_config.is_canyon_active(next_l2_time).then_some(Withdrawals {});
+        let parent_beacon_block_root = self
+            .rollup_config
+            .is_ecotone_active(next_l2_time)
+            .then(|| l1_header.parent_beacon_block_root.unwrap_or_default());
+        let gas_limit = u64::try_from(system_config.gas_limit).map_err(|_| {
+            anyhow!("System config gas limit {} exceeds u64", system_config.gas_limit)
+        })?;
+
+        self.system_config = system_config;
+        Ok(PayloadAttributes {
+            timestamp: next_l2_time,
+            prev_randao: l1_header.mix_hash,
+            fee_recipient: SystemAccounts::default().fee_vault,
+            withdrawals,
+            parent_beacon_block_root,
+            transactions,
+            no_tx_pool: true,
+            gas_limit: Some(gas_limit),
+        })
+    }
+
+    fn reset(&mut self, system_config: SystemConfig) {
+        self.system_config = system_config;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        traits::test_utils::TestChainProvider,
+        types::{BlockInfo, DepositSourceDomain, TxDeposit},
+        CONFIG_UPDATE_EVENT_VERSION_0, CONFIG_UPDATE_TOPIC, DEPOSIT_EVENT_ABI_HASH,
+    };
+    use alloy_consensus::{Header, Receipt};
+    use alloy_primitives::{address, Address, Bytes, Log, LogData, TxKind, B256, U256};
+
+    const DEPOSIT_CONTRACT: Address = address!("bEb5Fc579115071764c7423A4f12eDde41f106Ed");
+    const SYSTEM_CONFIG: Address = address!("229047fed2591dbec1eF1118d64F7aF3dB9EB290");
+
+    fn rollup_config() -> RollupConfig {
+        RollupConfig {
+            block_time: 2,
+            regolith_time: Some(0),
+            deposit_contract_address: DEPOSIT_CONTRACT,
+            l1_system_config_address: SYSTEM_CONFIG,
+            ..Default::default()
+        }
+    }
+
+    fn system_config() -> SystemConfig {
+        SystemConfig { gas_limit: U256::from(30_000_000), ..Default::default() }
+    }
+
+    /// Returns the hash and header of the L1 block with the given number and timestamp, whose
+    /// parent is the block with the preceding number.
+    fn l1_block(number: u64, timestamp: u64) -> (B256, Header) {
+        let header = Header {
+            parent_hash: B256::with_last_byte(number as u8 - 1),
+            number,
+            timestamp,
+            mix_hash: B256::repeat_byte(number as u8),
+            parent_beacon_block_root: Some(B256::repeat_byte(0xBE)),
+            ..Default::default()
+        };
+        (B256::with_last_byte(number as u8), header)
+    }
+
+    /// Returns an L2 parent at the given timestamp, in the epoch of the given L1 block number.
+    fn l2_parent(timestamp: u64, l1_origin: u64, seq_num: u64) -> L2BlockInfo {
+        L2BlockInfo {
+            block_info: BlockInfo { timestamp, ..Default::default() },
+            l1_origin: BlockID { hash: B256::with_last_byte(l1_origin as u8), number: l1_origin },
+            seq_num,
+        }
+    }
+
+    fn gas_limit_update(gas_limit: u64) -> Log {
+        let mut data = vec![0u8; 96];
+        data[31] = 32;
+        data[63] = 32;
+        data[88..].copy_from_slice(&gas_limit.to_be_bytes());
+        Log {
+            address: SYSTEM_CONFIG,
+            data: LogData::new_unchecked(
+                vec![CONFIG_UPDATE_TOPIC, CONFIG_UPDATE_EVENT_VERSION_0, B256::with_last_byte(2)],
+                data.into(),
+            ),
+        }
+    }
+
+    /// Returns a `TransactionDeposited` log of a call to the zero address with the given gas limit.
+    fn user_deposit(gas_limit: u64) -> Log {
+        let mut data = vec![0u8; 64 + 96];
+        data[31] = 32;
+        data[63] = 73;
+        data[128..136].copy_from_slice(&gas_limit.to_be_bytes());
+        Log {
+            address: DEPOSIT_CONTRACT,
+            data: LogData::new_unchecked(
+                vec![DEPOSIT_EVENT_ABI_HASH, B256::ZERO, B256::ZERO, B256::ZERO],
+                data.into(),
+            ),
+        }
+    }
+
+    fn builder(
+        rollup_config: RollupConfig,
+        blocks: &[(B256, Header, Vec<Receipt>)],
+    ) -> StatefulAttributesBuilder<TestChainProvider> {
+        let mut provider = TestChainProvider::default();
+        for (hash, header, receipts) in blocks {
+            provider.insert_header(*hash, header.clone());
+            provider.insert_receipts(*hash, receipts.clone());
+        }
+        StatefulAttributesBuilder::new(Arc::new(rollup_config), system_config(), provider)
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_attributes_new_epoch() {
+        let cfg = rollup_config();
+        let (hash, header) = l1_block(2, 10);
+        let receipts = vec![Receipt {
+            status: true,
+            cumulative_gas_used: 0,
+            logs: vec![gas_limit_update(40_000_000), user_deposit(21_000)],
+        }];
+        let mut builder = builder(cfg, &[(hash, header.clone(), receipts)]);
+
+        let epoch = BlockID { hash, number: 2 };
+        let attributes =
+            builder.prepare_payload_attributes(l2_parent(10, 1, 4), epoch).await.unwrap();
+
+        assert_eq!(attributes.timestamp, 12);
+        assert_eq!(attributes.prev_randao, header.mix_hash);
+        assert_eq!(attributes.fee_recipient, SystemAccounts::default().fee_vault);
+        assert_eq!(attributes.gas_limit, Some(40_000_000));
+        assert_eq!(builder.system_config().gas_limit, U256::from(40_000_000));
+        assert!(attributes.no_tx_pool);
+        assert_eq!(attributes.withdrawals, None);
+        assert_eq!(attributes.parent_beacon_block_root, None);
+
+        // The L1 info deposit starts a new sequence, and is followed by the user deposit.
+        let (_, l1_info_tx) = L1BlockInfoTx::try_new_with_deposit_tx(
+            &rollup_config(),
+            builder.system_config(),
+            0,
+            &header,
+            hash,
+            12,
+        )
+        .unwrap();
+        let user_tx = TxDeposit {
+            source_hash: DepositSourceDomain::User { l1_block_hash: hash, log_index: 1 }
+                .source_hash(),
+            to: TxKind::Call(Address::ZERO),
+            gas_limit: 21_000,
+            input: Bytes::new(),
+            ..Default::default()
+        };
+        assert_eq!(attributes.transactions, vec![l1_info_tx.into(), user_tx.into()]);
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_attributes_same_epoch() {
+        let (hash, header) = l1_block(1, 8);
+        let mut builder = builder(rollup_config(), &[(hash, header.clone(), vec![])]);
+
+        let epoch = BlockID { hash, number: 1 };
+        let attributes =
+            builder.prepare_payload_attributes(l2_parent(10, 1, 4), epoch).await.unwrap();
+
+        let (_, l1_info_tx) = L1BlockInfoTx::try_new_with_deposit_tx(
+            &rollup_config(),
+            &system_config(),
+            5,
+            &header,
+            hash,
+            12,
+        )
+        .unwrap();
+        assert_eq!(attributes.transactions, vec![l1_info_tx.into()]);
+        assert_eq!(attributes.gas_limit, Some(30_000_000));
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_attributes_epoch_mismatch() {
+        let (hash, header) = l1_block(2, 10);
+        let mut builder = builder(rollup_config(), &[(hash, header, vec![])]);
+
+        // The parent's L1 origin is not the parent of the new epoch.
+        let epoch = BlockID { hash, number: 2 };
+        let parent = L2BlockInfo {
+            l1_origin: BlockID { hash: B256::repeat_byte(0xFF), number: 1 },
+            ..l2_parent(10, 1, 0)
+        };
+        assert!(builder.prepare_payload_attributes(parent, epoch).await.is_err());
+
+        // The parent's L1 origin is not the epoch it claims to share.
+        let parent = L2BlockInfo {
+            l1_origin: BlockID { hash: B256::repeat_byte(0xFF), number: 2 },
+            ..l2_parent(10, 2, 0)
+        };
+        assert!(builder.prepare_payload_attributes(parent, epoch).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_attributes_before_l1_origin() {
+        let (hash, header) = l1_block(2, 20);
+        let mut builder = builder(rollup_config(), &[(hash, header, vec![])]);
+        let epoch = BlockID { hash, number: 2 };
+        assert!(builder.prepare_payload_attributes(l2_parent(10, 1, 0), epoch).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_attributes_hardforks() {
+        let cfg = RollupConfig { canyon_time: Some(0), ecotone_time: Some(12), ..rollup_config() };
+        let (hash, header) = l1_block(1, 8);
+        let mut builder = builder(cfg, &[(hash, header, vec![])]);
+        let epoch = BlockID { hash, number: 1 };
+
+        // The Ecotone activation block cannot be built without the upgrade deployments.
+        assert!(builder.prepare_payload_attributes(l2_parent(10, 1, 0), epoch).await.is_err());
+
+        // Later blocks do not contain them.
+        let attributes =
+            builder.prepare_payload_attributes(l2_parent(12, 1, 1), epoch).await.unwrap();
+        assert_eq!(attributes.withdrawals, Some(Withdrawals {}));
+        assert_eq!(attributes.parent_beacon_block_root, Some(B256::repeat_byte(0xBE)));
+        assert_eq!(attributes.transactions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reset_system_config() {
+        let (hash, header) = l1_block(2, 10);
+        let receipts = vec![Receipt {
+            status: true,
+            cumulative_gas_used: 0,
+            logs: vec![gas_limit_update(40_000_000)],
+        }];
+        let mut builder = builder(rollup_config(), &[(hash, header, receipts)]);
+        let epoch = BlockID { hash, number: 2 };
+        builder.prepare_payload_attributes(l2_parent(10, 1, 0), epoch).await.unwrap();
+        assert_eq!(builder.system_config().gas_limit, U256::from(40_000_000));
+
+        builder.reset(system_config());
+        assert_eq!(builder.system_config(), &system_config());
+    }
+}
@@ -3,8 +3,8 @@
 use crate::{
     traits::{LogLevel, OriginProvider, ResettableStage, TelemetryProvider},
     types::{
-        AttributesWithParent, BlockID, BlockInfo, L2BlockInfo, PayloadAttributes, ResetError,
-        RollupConfig, SingleBatch, StageError, StageResult, SystemConfig,
+        AttributesWithParent, BlockInfo, L2BlockInfo, PayloadAttributes, ResetError, RollupConfig,
+        SingleBatch, StageError, StageResult, SystemConfig,
     },
 };
 use alloc::boxed::Box;
@@ -12,14 +12,8 @@ use alloy_primitives::Bytes;
 use async_trait::async_trait;
 use core::fmt::Debug;
 
-pub trait AttributesBuilder {
-    /// Prepare the payload attributes.
-    fn prepare_payload_attributes(
-        &mut self,
-        l2_parent: L2BlockInfo,
-        epoch: BlockID,
-    ) -> anyhow::Result<PayloadAttributes>;
-}
+mod builder;
+pub use builder::{AttributesBuilder, StatefulAttributesBuilder};
 
 /// [AttributesProvider] is a trait abstraction that generalizes the [BatchQueue] stage.
 #[async_trait]
@@ -51,7 +45,7 @@ where
     /// The rollup config.
     cfg: RollupConfig,
     /// The previous stage of the derivation pipeline.
-    prev: P,
+    pub(crate) prev: P,
     /// Telemetry provider.
     telemetry: T,
     /// Whether the current batch is the last in its span.
@@ -128,6 +122,7 @@ where
         let mut attributes = self
             .builder
             .prepare_payload_attributes(parent, batch.epoch())
+            .await
             .map_err(StageError::AttributesBuild)?;
         attributes.no_tx_pool = true;
         attributes.transactions.extend(batch.transactions);
@@ -163,8 +158,9 @@ where
     T: TelemetryProvider + Send + Debug,
     AB: AttributesBuilder + Send + Debug,
 {
-    async fn reset(&mut self, _: BlockInfo, _: SystemConfig) -> StageResult<()> {
+    async fn reset(&mut self, _: BlockInfo, system_config: SystemConfig) -> StageResult<()> {
         self.telemetry.write(Bytes::from("resetting attributes queue"), LogLevel::Info);
+        self.builder.reset(system_config);
         // TODO: metrice the reset using telemetry
         // telemetry can provide a method of logging and metricing
         self.batch = None;
@@ -48,7 +48,7 @@ where
     /// The rollup config.
     cfg: RollupConfig,
     /// The previous stage of the derivation pipeline.
-    prev: P,
+    pub(crate) prev: P,
     /// The l1 block ref
     origin: Option<BlockInfo>,
 
@@ -177,9 +177,10 @@ where
 
         // If the current epoch is too old compared to the L1 block we are at,
         // i.e. if the sequence window expired, we create empty batches for the current epoch
+        let origin = self.origin.ok_or(StageError::MissingOrigin)?;
         let expiry_epoch = epoch.number + self.cfg.seq_window_size;
-        let force_empty_batches = (expiry_epoch == parent.l1_origin.number && empty) ||
-            expiry_epoch < parent.l1_origin.number;
+        let force_empty_batches =
+            (expiry_epoch == origin.number && empty) || expiry_epoch < origin.number;
         let first_of_epoch = epoch.number == parent.l1_origin.number + 1;
 
         // TODO: Log the empty batch generation.
@@ -40,13 +40,13 @@ where
     /// The rollup configuration.
     cfg: Arc<RollupConfig>,
     /// Telemetry
-    telemetry: Arc<T>,
+    telemetry: T,
     /// Map of channels by ID.
     channels: HashMap<ChannelID, Channel>,
     /// Channels in FIFO order.
     channel_queue: VecDeque<ChannelID>,
     /// The previous stage of the derivation pipeline.
-    prev: P,
+    pub(crate) prev: P,
 }
 
 impl<P, T> ChannelBank<P, T>
@@ -55,7 +55,7 @@ where
     T: TelemetryProvider + Debug,
 {
     /// Create a new [ChannelBank] stage.
-    pub fn new(cfg: Arc<RollupConfig>, prev: P, telemetry: Arc<T>) -> Self {
+    pub fn new(cfg: Arc<RollupConfig>, prev: P, telemetry: T) -> Self {
         Self { cfg, telemetry, channels: HashMap::new(), channel_queue: VecDeque::new(), prev }
     }
 
@@ -2,11 +2,11 @@
 
 use crate::{
     stages::BatchQueueProvider,
-    traits::{LogLevel, OriginProvider, TelemetryProvider},
-    types::{Batch, BlockInfo, StageError, StageResult},
+    traits::{LogLevel, OriginProvider, ResettableStage, TelemetryProvider},
+    types::{Batch, BlockInfo, StageError, StageResult, SystemConfig},
 };
 
-use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use alloc::{boxed::Box, vec::Vec};
 use alloy_primitives::Bytes;
 use async_trait::async_trait;
 use core::fmt::Debug;
@@ -30,9 +30,9 @@ where
     T: TelemetryProvider + Debug,
 {
     /// The previous stage of the derivation pipeline.
-    prev: P,
+    pub(crate) prev: P,
     /// Telemetry
-    telemetry: Arc<T>,
+    telemetry: T,
     /// The batch reader.
     next_batch: Option<BatchReader>,
 }
@@ -43,7 +43,7 @@ where
     T: TelemetryProvider + Debug,
 {
     /// Create a new [ChannelReader] stage.
-    pub fn new(prev: P, telemetry: Arc<T>) -> Self {
+    pub fn new(prev: P, telemetry: T) -> Self {
         Self { prev, telemetry, next_batch: None }
     }
 
@@ -102,6 +102,18 @@ where
     }
 }
 
+#[async_trait]
+impl<P, T> ResettableStage for ChannelReader<P, T>
+where
+    P: ChannelReaderProvider + OriginProvider + Send + Debug,
+    T: TelemetryProvider + Send + Sync + Debug,
+{
+    async fn reset(&mut self, _: BlockInfo, _: SystemConfig) -> StageResult<()> {
+        self.next_channel();
+        Err(StageError::Eof)
+    }
+}
+
 /// Batch Reader provides a function that iteratively consumes batches from the reader.
 /// The L1Inclusion block is also provided at creation time.
 /// Warning: the batch reader can read every batch-type.
@@ -155,7 +167,7 @@ mod test {
         stages::test_utils::MockChannelReaderProvider, traits::test_utils::TestTelemetry,
         types::BatchType,
     };
-    use alloc::vec;
+    use alloc::{sync::Arc, vec};
     use miniz_oxide::deflate::compress_to_vec_zlib;
 
     fn new_compressed_batch_data() -> Bytes {
@@ -5,7 +5,7 @@ use crate::types::{
     Blob, BlockInfo, ExecutionPayloadEnvelope, IndexedBlobHash, L2BlockInfo, StageResult,
 };
 use alloc::{boxed::Box, fmt::Debug, vec::Vec};
-use alloy_consensus::{Receipt, TxEnvelope};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
 use alloy_primitives::{Address, Bytes, B256};
 use anyhow::Result;
 use async_trait::async_trait;
@@ -39,6 +39,10 @@ pub trait OriginProvider {
 /// Describes the functionality of a data source that can provide information from the blockchain.
 #[async_trait]
 pub trait ChainProvider {
+    /// Returns the header of the block with the given hash, or an error if the block does not
+    /// exist in the data source.
+    async fn header_by_hash(&self, hash: B256) -> Result<Header>;
+
     /// Returns the block at the given number, or an error if the block does not exist in the data
     /// source.
     async fn block_info_by_number(&self, number: u64) -> Result<BlockInfo>;
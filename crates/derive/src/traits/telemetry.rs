@@ -1,5 +1,6 @@
 //! Traits for telemetry.
 
+use alloc::sync::Arc;
 use alloy_primitives::Bytes;
 
 /// Logging Levels.
@@ -21,3 +22,9 @@ pub trait TelemetryProvider {
     /// Write the telemetry data with LOG_LEVEL.
     fn write<I: Into<Bytes>>(&self, data: I, level: LogLevel);
 }
+
+impl<T: TelemetryProvider + ?Sized> TelemetryProvider for Arc<T> {
+    fn write<I: Into<Bytes>>(&self, data: I, level: LogLevel) {
+        (**self).write(data, level)
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     types::{BlockInfo, ExecutionPayloadEnvelope, L2BlockInfo},
 };
 use alloc::{boxed::Box, vec::Vec};
-use alloy_consensus::{Receipt, TxEnvelope};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
 use alloy_primitives::B256;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -52,6 +52,8 @@ pub struct TestChainProvider {
     pub blocks: Vec<(u64, BlockInfo)>,
     /// Maps block hashes to receipts using a tuple list.
     pub receipts: Vec<(B256, Vec<Receipt>)>,
+    /// Maps block hashes to headers using a tuple list.
+    pub headers: Vec<(B256, Header)>,
 }
 
 impl TestChainProvider {
@@ -65,6 +67,11 @@ impl TestChainProvider {
         self.receipts.push((hash, receipts));
     }
 
+    /// Insert a header into the mock chain provider.
+    pub fn insert_header(&mut self, hash: B256, header: Header) {
+        self.headers.push((hash, header));
+    }
+
     /// Clears blocks from the mock chain provider.
     pub fn clear_blocks(&mut self) {
         self.blocks.clear();
@@ -75,15 +82,29 @@ impl TestChainProvider {
         self.receipts.clear();
     }
 
-    /// Clears all blocks and receipts from the mock chain provider.
+    /// Clears headers from the mock chain provider.
+    pub fn clear_headers(&mut self) {
+        self.headers.clear();
+    }
+
+    /// Clears all blocks, receipts and headers from the mock chain provider.
     pub fn clear(&mut self) {
         self.clear_blocks();
         self.clear_receipts();
+        self.clear_headers();
     }
 }
 
 #[async_trait]
 impl ChainProvider for TestChainProvider {
+    async fn header_by_hash(&self, hash: B256) -> Result<Header> {
+        if let Some((_, header)) = self.headers.iter().find(|(h, _)| *h == hash) {
+            Ok(header.clone())
+        } else {
+            Err(anyhow::anyhow!("Header not found"))
+        }
+    }
+
     async fn block_info_by_number(&self, _number: u64) -> Result<BlockInfo> {
         if let Some((_, block)) = self.blocks.iter().find(|(n, _)| *n == _number) {
             Ok(*block)
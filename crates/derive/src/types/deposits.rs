@@ -0,0 +1,508 @@
+//! This module contains the deposit transaction type, and the derivation of user deposits from L1
+//! receipts.
+
+use super::RawTransaction;
+use crate::params::DEPOSIT_EVENT_ABI_HASH;
+use alloc::{string::String, vec::Vec};
+use alloy_consensus::Receipt;
+use alloy_primitives::{keccak256, Address, Bytes, Log, TxKind, B256, U256};
+use alloy_rlp::{Decodable, Encodable, Header};
+use anyhow::{anyhow, bail, Result};
+
+/// The EIP-2718 transaction type of a deposit transaction.
+pub const DEPOSIT_TX_TYPE: u8 = 0x7E;
+
+/// The version of the `TransactionDeposited` event emitted by the deposit contract whose opaque
+/// data is `mint ++ value ++ gas ++ isCreation ++ data`.
+pub const DEPOSIT_EVENT_VERSION_0: B256 = B256::ZERO;
+
+/// A deposit transaction, which is included in an L2 block by the rollup node rather than being
+/// signed by a sender.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TxDeposit {
+    /// Uniquely identifies the origin of the deposit. See [DepositSourceDomain].
+    pub source_hash: B256,
+    /// The address of the sender account.
+    pub from: Address,
+    /// The address of the recipient account, or [TxKind::Create] for a contract creation.
+    pub to: TxKind,
+    /// The ETH value to mint on L2.
+    pub mint: U256,
+    /// The ETH value to send to the recipient account.
+    pub value: U256,
+    /// The gas limit for the L2 transaction.
+    #[cfg_attr(feature = "serde", serde(rename = "gas"))]
+    pub gas_limit: u64,
+    /// Whether the transaction is exempt from the L2 gas limit. Only used before Regolith.
+    #[cfg_attr(feature = "serde", serde(rename = "isSystemTx"))]
+    pub is_system_transaction: bool,
+    /// The calldata, or the init code for a contract creation.
+    pub input: Bytes,
+}
+
+impl TxDeposit {
+    /// Returns the length of the RLP-encoded fields, without the list header.
+    fn fields_len(&self) -> usize {
+        self.source_hash.length() +
+            self.from.length() +
+            self.to.length() +
+            self.mint.length() +
+            self.value.length() +
+            self.gas_limit.length() +
+            self.is_system_transaction.length() +
+            self.input.length()
+    }
+
+    /// RLP-encodes the fields of the transaction, without the list header.
+    fn encode_fields(&self, out: &mut dyn alloy_rlp::BufMut) {
+        self.source_hash.encode(out);
+        self.from.encode(out);
+        self.to.encode(out);
+        self.mint.encode(out);
+        self.value.encode(out);
+        self.gas_limit.encode(out);
+        self.is_system_transaction.encode(out);
+        self.input.encode(out);
+    }
+
+    /// Encodes the transaction in its EIP-2718 form, `0x7E ++ rlp([fields...])`, as it appears in
+    /// the transaction list of an L2 block.
+    pub fn encode_2718(&self) -> Bytes {
+        let mut out = Vec::with_capacity(1 + self.length());
+        out.push(DEPOSIT_TX_TYPE);
+        self.encode(&mut out);
+        out.into()
+    }
+
+    /// Decodes a transaction from its EIP-2718 form, `0x7E ++ rlp([fields...])`.
+    pub fn decode_2718(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        match buf.first() {
+            Some(&DEPOSIT_TX_TYPE) => *buf = &buf[1..],
+            Some(_) => return Err(alloy_rlp::Error::Custom("Not a deposit transaction")),
+            None => return Err(alloy_rlp::Error::InputTooShort),
+        }
+        Self::decode(buf)
+    }
+
+    /// Returns the transaction hash, the keccak256 hash of the EIP-2718 encoding.
+    pub fn tx_hash(&self) -> B256 {
+        keccak256(self.encode_2718())
+    }
+}
+
+impl Encodable for TxDeposit {
+    /// Encodes the fields of the transaction as an RLP list, without the transaction type.
+    fn encode(&self, out: &mut dyn alloy_rlp::BufMut) {
+        Header { list: true, payload_length: self.fields_len() }.encode(out);
+        self.encode_fields(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.fields_len();
+        Header { list: true, payload_length }.length() + payload_length
+    }
+}
+
+impl Decodable for TxDeposit {
+    /// Decodes the fields of the transaction from an RLP list, without the transaction type.
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        let remaining = buf.len();
+        let tx = Self {
+            source_hash: Decodable::decode(buf)?,
+            from: Decodable::decode(buf)?,
+            to: Decodable::decode(buf)?,
+            mint: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            is_system_transaction: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+        };
+        if remaining - buf.len() != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: remaining - buf.len(),
+            });
+        }
+        Ok(tx)
+    }
+}
+
+impl TryFrom<&RawTransaction> for TxDeposit {
+    type Error = alloy_rlp::Error;
+
+    fn try_from(tx: &RawTransaction) -> Result<Self, Self::Error> {
+        Self::decode_2718(&mut tx.0.as_ref())
+    }
+}
+
+impl From<TxDeposit> for RawTransaction {
+    fn from(tx: TxDeposit) -> Self {
+        Self(tx.encode_2718())
+    }
+}
+
+/// The domain of a deposit's source hash. Each domain commits to a different origin, so that the
+/// source hashes of deposits from different domains can never collide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepositSourceDomain {
+    /// A user deposit, emitted as a `TransactionDeposited` log by the deposit contract on L1.
+    User {
+        /// The hash of the L1 block that contains the deposit log.
+        l1_block_hash: B256,
+        /// The index of the deposit log within the L1 block.
+        log_index: u64,
+    },
+    /// The L1 info deposit at the start of every L2 block.
+    L1Info {
+        /// The hash of the L1 origin of the L2 block.
+        l1_block_hash: B256,
+        /// The sequence number of the L2 block within its epoch.
+        seq_number: u64,
+    },
+    /// A deposit that is inserted by the rollup node to upgrade the L2 chain at a hardfork.
+    Upgrade {
+        /// The human-readable intent of the upgrade transaction.
+        intent: String,
+    },
+}
+
+impl DepositSourceDomain {
+    /// Returns the identifier of the domain.
+    pub fn identifier(&self) -> u64 {
+        match self {
+            Self::User { .. } => 0,
+            Self::L1Info { .. } => 1,
+            Self::Upgrade { .. } => 2,
+        }
+    }
+
+    /// Returns the source hash, `keccak256(bytes32(domain) ++ keccak256(origin))`.
+    pub fn source_hash(&self) -> B256 {
+        let inner = match self {
+            Self::User { l1_block_hash, log_index } => {
+                let mut buf = [0u8; 64];
+                buf[..32].copy_from_slice(l1_block_hash.as_slice());
+                buf[56..].copy_from_slice(&log_index.to_be_bytes());
+                keccak256(buf)
+            }
+            Self::L1Info { l1_block_hash, seq_number } => {
+                let mut buf = [0u8; 64];
+                buf[..32].copy_from_slice(l1_block_hash.as_slice());
+                buf[56..].copy_from_slice(&seq_number.to_be_bytes());
+                keccak256(buf)
+            }
+            Self::Upgrade { intent } => keccak256(intent.as_bytes()),
+        };
+
+        let mut buf = [0u8; 64];
+        buf[24..32].copy_from_slice(&self.identifier().to_be_bytes());
+        buf[32..].copy_from_slice(inner.as_slice());
+        keccak256(buf)
+    }
+}
+
+/// Derives the user deposits of an L1 block from its receipts, in the order that they were emitted,
+/// and encodes them for inclusion in an L2 block. See [deposits_from_receipts].
+pub fn derive_deposits(
+    l1_block_hash: B256,
+    receipts: &[Receipt],
+    deposit_contract: Address,
+) -> Result<Vec<RawTransaction>> {
+    let deposits = deposits_from_receipts(l1_block_hash, receipts, deposit_contract)?;
+    Ok(deposits.into_iter().map(RawTransaction::from).collect())
+}
+
+/// Decodes the user deposits of an L1 block from its receipts, in the order that they were emitted.
+/// Only `TransactionDeposited` logs emitted by the `deposit_contract` in successful receipts are
+/// considered.
+///
+/// # Returns
+/// - `Ok(Vec<TxDeposit>)` with the deposit transactions.
+/// - `Err(_)` if any deposit log is malformed.
+pub fn deposits_from_receipts(
+    l1_block_hash: B256,
+    receipts: &[Receipt],
+    deposit_contract: Address,
+) -> Result<Vec<TxDeposit>> {
+    let mut deposits = Vec::new();
+    let mut log_index = 0u64;
+    for receipt in receipts {
+        // Logs of failed receipts are dropped, but they still count towards the log index.
+        if !receipt.status {
+            log_index += receipt.logs.len() as u64;
+            continue;
+        }
+
+        for log in receipt.logs.iter() {
+            if log.address == deposit_contract &&
+                log.topics().first() == Some(&DEPOSIT_EVENT_ABI_HASH)
+            {
+                let deposit = decode_deposit(l1_block_hash, log_index, log)
+                    .map_err(|e| anyhow!("Malformed deposit log at index {log_index}: {e}"))?;
+                deposits.push(deposit);
+            }
+            log_index += 1;
+        }
+    }
+    Ok(deposits)
+}
+
+/// Decodes a `TransactionDeposited` log into a [TxDeposit].
+///
+/// ```text
+/// event TransactionDeposited(
+///    address indexed from,
+///    address indexed to,
+///    uint256 indexed version,
+///    bytes opaqueData
+/// );
+/// ```
+pub fn decode_deposit(l1_block_hash: B256, log_index: u64, log: &Log) -> Result<TxDeposit> {
+    let topics = log.topics();
+    if topics.len() != 4 {
+        bail!("Expected 4 event topics, got {}", topics.len());
+    }
+    if topics[0] != DEPOSIT_EVENT_ABI_HASH {
+        bail!("Invalid deposit event selector {}", topics[0]);
+    }
+
+    let data = log.data.data.as_ref();
+    if data.len() < 64 || data.len() % 32 != 0 {
+        bail!("Invalid deposit event data length {}", data.len());
+    }
+
+    // The event data is the ABI encoding of the single `bytes` argument.
+    let offset = U256::from_be_slice(&data[..32]);
+    if offset != U256::from(32) {
+        bail!("Invalid opaque data offset {offset}");
+    }
+    let length: usize = U256::from_be_slice(&data[32..64])
+        .try_into()
+        .map_err(|_| anyhow!("Opaque data length does not fit in usize"))?;
+    let padded_length = length.div_ceil(32).checked_mul(32);
+    if padded_length != Some(data.len() - 64) {
+        bail!("Opaque data length {length} does not match the event data length {}", data.len());
+    }
+    let opaque_data = &data[64..64 + length];
+
+    let from = Address::from_slice(&topics[1][12..]);
+    let to = Address::from_slice(&topics[2][12..]);
+    let source_hash = DepositSourceDomain::User { l1_block_hash, log_index }.source_hash();
+
+    // The layout of the opaque data is determined by the version of the event.
+    match topics[3] {
+        DEPOSIT_EVENT_VERSION_0 => decode_opaque_data_v0(source_hash, from, to, opaque_data),
+        version => bail!("Unsupported deposit event version {version}"),
+    }
+}
+
+/// Decodes the opaque data of a version 0 `TransactionDeposited` event:
+/// `mint (32) ++ value (32) ++ gas (8) ++ isCreation (1) ++ data`.
+fn decode_opaque_data_v0(
+    source_hash: B256,
+    from: Address,
+    to: Address,
+    opaque_data: &[u8],
+) -> Result<TxDeposit> {
+    if opaque_data.len() < 73 {
+        bail!("Opaque data too short: {} < 73", opaque_data.len());
+    }
+    let mint = U256::from_be_slice(&opaque_data[..32]);
+    let value = U256::from_be_slice(&opaque_data[32..64]);
+    let gas_limit = u64::from_be_bytes(opaque_data[64..72].try_into()?);
+    let to = if opaque_data[72] != 0 { TxKind::Create } else { TxKind::Call(to) };
+    let input = Bytes::copy_from_slice(&opaque_data[73..]);
+
+    Ok(TxDeposit {
+        source_hash,
+        from,
+        to,
+        mint,
+        value,
+        gas_limit,
+        is_system_transaction: false,
+        input,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec};
+    use alloy_primitives::{address, b256, hex, LogData};
+
+    /// Builds a version 0 `TransactionDeposited` log.
+    fn deposit_log(contract: Address, from: Address, to: Address, opaque_data: &[u8]) -> Log {
+        let mut data = vec![0u8; 64];
+        data[31] = 32;
+        data[56..64].copy_from_slice(&(opaque_data.len() as u64).to_be_bytes());
+        data.extend_from_slice(opaque_data);
+        data.resize(64 + opaque_data.len().div_ceil(32) * 32, 0);
+        Log {
+            address: contract,
+            data: LogData::new_unchecked(
+                vec![DEPOSIT_EVENT_ABI_HASH, from.into_word(), to.into_word(), B256::ZERO],
+                data.into(),
+            ),
+        }
+    }
+
+    fn opaque_data(mint: u64, value: u64, gas: u64, is_creation: bool, input: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&U256::from(mint).to_be_bytes::<32>());
+        data.extend_from_slice(&U256::from(value).to_be_bytes::<32>());
+        data.extend_from_slice(&gas.to_be_bytes());
+        data.push(is_creation as u8);
+        data.extend_from_slice(input);
+        data
+    }
+
+    #[test]
+    fn test_source_hashes() {
+        let l1_block_hash = B256::repeat_byte(0xAA);
+        let mut origin = [0u8; 64];
+        origin[..32].copy_from_slice(l1_block_hash.as_slice());
+        origin[63] = 4;
+        let mut expected = [0u8; 64];
+        expected[32..].copy_from_slice(keccak256(origin).as_slice());
+
+        let user = DepositSourceDomain::User { l1_block_hash, log_index: 4 };
+        assert_eq!(user.source_hash(), keccak256(expected));
+
+        let l1_info = DepositSourceDomain::L1Info { l1_block_hash, seq_number: 4 };
+        expected[31] = 1;
+        assert_eq!(l1_info.source_hash(), keccak256(expected));
+
+        // Test vector from the Ecotone network upgrade specification.
+        let upgrade =
+            DepositSourceDomain::Upgrade { intent: "Ecotone: L1 Block Deployment".to_string() };
+        assert_eq!(
+            upgrade.source_hash(),
+            b256!("877a6077205782ea15a6dc8699fa5ebcec5e0f4389f09cb8eda09488231346f8")
+        );
+    }
+
+    #[test]
+    fn test_encode_2718() {
+        let tx = TxDeposit {
+            source_hash: B256::repeat_byte(0x01),
+            from: address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001"),
+            to: TxKind::Call(address!("4200000000000000000000000000000000000015")),
+            mint: U256::ZERO,
+            value: U256::ZERO,
+            gas_limit: 1_000_000,
+            is_system_transaction: false,
+            input: hex!("deadbeef").into(),
+        };
+        let encoded = tx.encode_2718();
+        assert_eq!(encoded[0], DEPOSIT_TX_TYPE);
+        let header = Header::decode(&mut &encoded[1..]).unwrap();
+        assert!(header.list);
+        assert_eq!(encoded.len(), 1 + header.length() + header.payload_length);
+        assert_eq!(encoded.len(), 1 + tx.length());
+        assert_eq!(TxDeposit::decode_2718(&mut encoded.as_ref()).unwrap(), tx);
+        assert_eq!(TxDeposit::decode(&mut &encoded[1..]).unwrap(), tx);
+        assert!(TxDeposit::decode_2718(&mut &encoded[1..]).is_err());
+        assert_eq!(tx.tx_hash(), keccak256(&encoded));
+
+        let raw = RawTransaction::from(tx.clone());
+        assert!(raw.is_deposit());
+        assert_eq!(TxDeposit::try_from(&raw).unwrap(), tx);
+    }
+
+    #[test]
+    fn test_decode_trailing_fields() {
+        let tx = TxDeposit { to: TxKind::Create, gas_limit: 21_000, ..Default::default() };
+        let mut fields = Vec::new();
+        tx.encode_fields(&mut fields);
+        true.encode(&mut fields);
+        let mut encoded = Vec::new();
+        Header { list: true, payload_length: fields.len() }.encode(&mut encoded);
+        encoded.extend_from_slice(&fields);
+        assert!(TxDeposit::decode(&mut encoded.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_derive_deposits() {
+        let contract = address!("bEb5Fc579115071764c7423A4f12eDde41f106Ed");
+        let from = address!("1111111111111111111111111111111111111111");
+        let to = address!("2222222222222222222222222222222222222222");
+        let l1_block_hash = B256::repeat_byte(0xAA);
+
+        let call = deposit_log(contract, from, to, &opaque_data(1, 2, 21_000, false, &[0xFF]));
+        let create = deposit_log(contract, from, to, &opaque_data(0, 0, 100_000, true, &[]));
+        let other = Log { address: to, data: call.data.clone() };
+        let receipts = vec![
+            Receipt { status: true, cumulative_gas_used: 0, logs: vec![other, call] },
+            Receipt { status: false, cumulative_gas_used: 0, logs: vec![create.clone()] },
+            Receipt { status: true, cumulative_gas_used: 0, logs: vec![create] },
+        ];
+
+        let deposits = derive_deposits(l1_block_hash, &receipts, contract).unwrap();
+        assert_eq!(deposits.len(), 2);
+
+        let expected_call = TxDeposit {
+            source_hash: DepositSourceDomain::User { l1_block_hash, log_index: 1 }.source_hash(),
+            from,
+            to: TxKind::Call(to),
+            mint: U256::from(1),
+            value: U256::from(2),
+            gas_limit: 21_000,
+            is_system_transaction: false,
+            input: Bytes::from_static(&[0xFF]),
+        };
+        assert_eq!(deposits[0], expected_call.into());
+
+        // The log of the failed receipt still counts towards the log index.
+        let expected_create = TxDeposit {
+            source_hash: DepositSourceDomain::User { l1_block_hash, log_index: 3 }.source_hash(),
+            from,
+            to: TxKind::Create,
+            mint: U256::ZERO,
+            value: U256::ZERO,
+            gas_limit: 100_000,
+            is_system_transaction: false,
+            input: Bytes::new(),
+        };
+        assert_eq!(deposits[1], expected_create.into());
+    }
+
+    #[test]
+    fn test_decode_deposit_is_creation() {
+        // Any non-zero isCreation byte marks a contract creation.
+        let contract = Address::ZERO;
+        let mut data = opaque_data(0, 0, 0, false, &[]);
+        data[72] = 2;
+        let log = deposit_log(contract, contract, contract, &data);
+        assert_eq!(decode_deposit(B256::ZERO, 0, &log).unwrap().to, TxKind::Create);
+    }
+
+    #[test]
+    fn test_decode_deposit_malformed() {
+        let contract = Address::ZERO;
+        let log = deposit_log(contract, contract, contract, &[0u8; 72]);
+        assert!(decode_deposit(B256::ZERO, 0, &log).is_err());
+
+        // An opaque data length that overflows when padded is rejected.
+        let log = deposit_log(contract, contract, contract, &[]);
+        let mut data = log.data.data.to_vec();
+        data[56..64].copy_from_slice(&usize::MAX.to_be_bytes());
+        let log = Log {
+            address: contract,
+            data: LogData::new_unchecked(log.topics().to_vec(), data.into()),
+        };
+        assert!(decode_deposit(B256::ZERO, 0, &log).is_err());
+
+        let mut log = deposit_log(contract, contract, contract, &opaque_data(0, 0, 0, false, &[]));
+        let mut topics = log.topics().to_vec();
+        topics[3] = B256::with_last_byte(1);
+        log.data = LogData::new_unchecked(topics, log.data.data.clone());
+        assert!(decode_deposit(B256::ZERO, 0, &log).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! This module contains the network upgrade transactions of the Ecotone activation block, as
+//! specified by the Ecotone network upgrade, and vendored from `op-node`.
+
+use super::{RawTransaction, TxDeposit};
+use alloc::vec::Vec;
+use alloy_primitives::{address, b256, bytes, Address, Bytes, TxKind, B256, U256};
+use anyhow::{bail, Result};
+
+/// The source hash of the `L1Block` deployment, for the intent "Ecotone: L1 Block Deployment".
+pub const ECOTONE_L1_BLOCK_DEPLOYMENT_SOURCE_HASH: B256 =
+    b256!("877a6077205782ea15a6dc8699fa5ebcec5e0f4389f09cb8eda09488231346f8");
+
+/// The source hash of the `GasPriceOracle` deployment, for the intent "Ecotone: Gas Price Oracle
+/// Deployment".
+pub const ECOTONE_GAS_PRICE_ORACLE_DEPLOYMENT_SOURCE_HASH: B256 =
+    b256!("a312b4510adf943510f05fcc8f15f86995a5066bd83ce11384688ae20e6ecf42");
+
+/// The source hash of the `L1Block` proxy update, for the intent "Ecotone: L1 Block Proxy
+/// Update".
+pub const ECOTONE_L1_BLOCK_PROXY_UPDATE_SOURCE_HASH: B256 =
+    b256!("18acb38c5ff1c238a7460ebc1b421fa49ec4874bdf1e0a530d234104e5e67dbc");
+
+/// The source hash of the `GasPriceOracle` proxy update, for the intent "Ecotone: Gas Price
+/// Oracle Proxy Update".
+pub const ECOTONE_GAS_PRICE_ORACLE_PROXY_UPDATE_SOURCE_HASH: B256 =
+    b256!("ee4f9385eceef498af0be7ec5862229f426dec41c8d42397c7257a5117d9230a");
+
+/// The source hash of the EIP-4788 beacon block roots contract deployment, for the intent
+/// "Ecotone: beacon block roots contract deployment".
+pub const ECOTONE_BEACON_ROOTS_DEPLOYMENT_SOURCE_HASH: B256 =
+    b256!("69b763c48478b9dc2f65ada09b3d92133ec592ea715ec65ad6e7f3dc519dc00c");
+
+/// The source hash of the call that enables Ecotone in the `GasPriceOracle`, for the intent
+/// "Ecotone: Gas Price Oracle Set Ecotone".
+pub const ECOTONE_ENABLE_GAS_PRICE_ORACLE_SOURCE_HASH: B256 =
+    b256!("0c1cb38e99dbc9cbfab3bb80863380b0905290b37eb3d6ab18dc01c1f3e75f93");
+
+/// The account that deploys the Ecotone `L1Block` implementation.
+pub const ECOTONE_L1_BLOCK_DEPLOYER: Address = address!("4210000000000000000000000000000000000000");
+
+/// The account that deploys the Ecotone `GasPriceOracle` implementation.
+pub const ECOTONE_GAS_PRICE_ORACLE_DEPLOYER: Address =
+    address!("4210000000000000000000000000000000000001");
+
+/// The address of the Ecotone `L1Block` implementation, the first contract created by the
+/// [ECOTONE_L1_BLOCK_DEPLOYER].
+pub const ECOTONE_L1_BLOCK_ADDRESS: Address = address!("07dbe8500fc591d1852B76feE44d5a05e13097Ff");
+
+/// The address of the Ecotone `GasPriceOracle` implementation, the first contract created by the
+/// [ECOTONE_GAS_PRICE_ORACLE_DEPLOYER].
+pub const ECOTONE_GAS_PRICE_ORACLE_ADDRESS: Address =
+    address!("b528D11cC114E026F138fE568744c6D45ce6Da7A");
+
+/// The `L1Block` predeploy proxy.
+pub const L1_BLOCK_PROXY: Address = address!("4200000000000000000000000000000000000015");
+
+/// The `GasPriceOracle` predeploy proxy.
+pub const GAS_PRICE_ORACLE_PROXY: Address = address!("420000000000000000000000000000000000000F");
+
+/// The sender of the presigned EIP-4788 beacon block roots contract deployment.
+pub const BEACON_ROOTS_DEPLOYER: Address = address!("0B799C86a49DEeb90402691F1041aa3AF2d3C875");
+
+/// The address of the EIP-4788 beacon block roots contract.
+pub const BEACON_ROOTS_ADDRESS: Address = address!("000F3df6D732807Ef1319fB7B8bB8522d0Beac02");
+
+/// The creation code of the EIP-4788 beacon block roots contract.
+pub const BEACON_ROOTS_DEPLOYMENT_BYTECODE: Bytes = bytes!("60618060095f395ff33373fffffffffffffffffffffffffffffffffffffffe14604d57602036146024575f5ffd5b5f35801560495762001fff810690815414603c575f5ffd5b62001fff01545f5260205ff35b5f5ffd5b62001fff42064281555f359062001fff015500");
+
+/// The selector of `upgradeTo(address)`, which points a predeploy proxy at a new implementation.
+pub const UPGRADE_TO_SELECTOR: [u8; 4] = [0x36, 0x59, 0xcf, 0xe6];
+
+/// The selector of `setEcotone()` on the `GasPriceOracle`.
+pub const SET_ECOTONE_SELECTOR: [u8; 4] = [0x22, 0xb9, 0x0a, 0xb3];
+
+/// The account that calls `setEcotone()` on the `GasPriceOracle`, the depositor account.
+pub const SET_ECOTONE_CALLER: Address = address!("DeaDDEaDDeAdDeAdDEAdDEaddeAddEAdDEAd0001");
+
+/// Returns the network upgrade transactions of the Ecotone activation block, in the order that
+/// they are included after the L1 info and user deposits:
+/// 1. The deployment of the Ecotone `L1Block` implementation.
+/// 2. The deployment of the Ecotone `GasPriceOracle` implementation.
+/// 3. The update of the `L1Block` proxy to the new implementation.
+/// 4. The update of the `GasPriceOracle` proxy to the new implementation.
+/// 5. The deployment of the EIP-4788 beacon block roots contract.
+/// 6. The `setEcotone()` call that enables the Ecotone fee parameters in the `GasPriceOracle`.
+///
+/// # Returns
+/// - `Err(_)` while the creation code of the Ecotone `L1Block` and `GasPriceOracle` implementations
+///   is not vendored into this crate. The activation block cannot be built without it, and an
+///   activation block without the deployments would be invalid.
+pub fn ecotone_upgrade_transactions() -> Result<Vec<RawTransaction>> {
+    let mut transactions = ecotone_deployments()?;
+    transactions.extend(ecotone_upgrade_calls());
+    Ok(transactions)
+}
+
+/// Returns the deployments of the Ecotone `L1Block` and `GasPriceOracle` implementations, the
+/// first two network upgrade transactions of the Ecotone activation block.
+///
+/// # Returns
+/// - `Err(_)` while their creation code is not vendored into this crate.
+fn ecotone_deployments() -> Result<Vec<RawTransaction>> {
+    bail!(
+        "The creation code of the Ecotone L1Block and GasPriceOracle implementations is not \
+         vendored, so the Ecotone activation block cannot be built"
+    )
+}
+
+/// Returns the proxy updates, beacon block roots deployment and `setEcotone()` call of the
+/// Ecotone activation block, which follow the two implementation deployments.
+fn ecotone_upgrade_calls() -> Vec<RawTransaction> {
+    let deposits = [
+        upgrade_deposit(
+            ECOTONE_L1_BLOCK_PROXY_UPDATE_SOURCE_HASH,
+            Address::ZERO,
+            TxKind::Call(L1_BLOCK_PROXY),
+            50_000,
+            upgrade_to_calldata(ECOTONE_L1_BLOCK_ADDRESS),
+        ),
+        upgrade_deposit(
+            ECOTONE_GAS_PRICE_ORACLE_PROXY_UPDATE_SOURCE_HASH,
+            Address::ZERO,
+            TxKind::Call(GAS_PRICE_ORACLE_PROXY),
+            50_000,
+            upgrade_to_calldata(ECOTONE_GAS_PRICE_ORACLE_ADDRESS),
+        ),
+        upgrade_deposit(
+            ECOTONE_BEACON_ROOTS_DEPLOYMENT_SOURCE_HASH,
+            BEACON_ROOTS_DEPLOYER,
+            TxKind::Create,
+            250_000,
+            BEACON_ROOTS_DEPLOYMENT_BYTECODE,
+        ),
+        upgrade_deposit(
+            ECOTONE_ENABLE_GAS_PRICE_ORACLE_SOURCE_HASH,
+            SET_ECOTONE_CALLER,
+            TxKind::Call(GAS_PRICE_ORACLE_PROXY),
+            80_000,
+            Bytes::from_static(&SET_ECOTONE_SELECTOR),
+        ),
+    ];
+    deposits.into_iter().map(RawTransaction::from).collect()
+}
+
+/// Returns a network upgrade deposit, which mints and sends no ETH.
+fn upgrade_deposit(
+    source_hash: B256,
+    from: Address,
+    to: TxKind,
+    gas_limit: u64,
+    input: Bytes,
+) -> TxDeposit {
+    TxDeposit {
+        source_hash,
+        from,
+        to,
+        mint: U256::ZERO,
+        value: U256::ZERO,
+        gas_limit,
+        is_system_transaction: false,
+        input,
+    }
+}
+
+/// Returns the calldata of `upgradeTo(implementation)`.
+fn upgrade_to_calldata(implementation: Address) -> Bytes {
+    let mut calldata = Vec::with_capacity(36);
+    calldata.extend_from_slice(&UPGRADE_TO_SELECTOR);
+    calldata.extend_from_slice(implementation.into_word().as_slice());
+    calldata.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DepositSourceDomain;
+    use alloc::string::ToString;
+    use alloy_primitives::{hex, keccak256};
+
+    #[test]
+    fn test_ecotone_source_hashes() {
+        let intents = [
+            ("Ecotone: L1 Block Deployment", ECOTONE_L1_BLOCK_DEPLOYMENT_SOURCE_HASH),
+            (
+                "Ecotone: Gas Price Oracle Deployment",
+                ECOTONE_GAS_PRICE_ORACLE_DEPLOYMENT_SOURCE_HASH,
+            ),
+            ("Ecotone: L1 Block Proxy Update", ECOTONE_L1_BLOCK_PROXY_UPDATE_SOURCE_HASH),
+            (
+                "Ecotone: Gas Price Oracle Proxy Update",
+                ECOTONE_GAS_PRICE_ORACLE_PROXY_UPDATE_SOURCE_HASH,
+            ),
+            (
+                "Ecotone: beacon block roots contract deployment",
+                ECOTONE_BEACON_ROOTS_DEPLOYMENT_SOURCE_HASH,
+            ),
+            ("Ecotone: Gas Price Oracle Set Ecotone", ECOTONE_ENABLE_GAS_PRICE_ORACLE_SOURCE_HASH),
+        ];
+        for (intent, source_hash) in intents {
+            let domain = DepositSourceDomain::Upgrade { intent: intent.to_string() };
+            assert_eq!(domain.source_hash(), source_hash, "{intent}");
+        }
+    }
+
+    #[test]
+    fn test_ecotone_addresses() {
+        assert_eq!(ECOTONE_L1_BLOCK_DEPLOYER.create(0), ECOTONE_L1_BLOCK_ADDRESS);
+        assert_eq!(ECOTONE_GAS_PRICE_ORACLE_DEPLOYER.create(0), ECOTONE_GAS_PRICE_ORACLE_ADDRESS);
+        assert_eq!(BEACON_ROOTS_DEPLOYER.create(0), BEACON_ROOTS_ADDRESS);
+    }
+
+    #[test]
+    fn test_ecotone_selectors() {
+        assert_eq!(keccak256("upgradeTo(address)")[..4], UPGRADE_TO_SELECTOR);
+        assert_eq!(keccak256("setEcotone()")[..4], SET_ECOTONE_SELECTOR);
+    }
+
+    #[test]
+    fn test_beacon_roots_bytecode() {
+        // The 9 byte creation code returns the runtime code that follows it, of the length it
+        // pushes first.
+        let code = BEACON_ROOTS_DEPLOYMENT_BYTECODE;
+        assert_eq!(code[..2], hex!("6061"));
+        assert_eq!(code.len(), 9 + 0x61);
+    }
+
+    #[test]
+    fn test_ecotone_upgrade_calls() {
+        let calls = ecotone_upgrade_calls();
+        let deposits = calls.iter().map(|tx| TxDeposit::try_from(tx).unwrap()).collect::<Vec<_>>();
+        assert_eq!(deposits.len(), 4);
+
+        assert_eq!(deposits[0].to, TxKind::Call(L1_BLOCK_PROXY));
+        assert_eq!(
+            deposits[0].input,
+            Bytes::from(hex!(
+                "3659cfe600000000000000000000000007dbe8500fc591d1852b76fee44d5a05e13097ff"
+            ))
+        );
+        assert_eq!(deposits[1].to, TxKind::Call(GAS_PRICE_ORACLE_PROXY));
+        assert_eq!(
+            deposits[1].input,
+            Bytes::from(hex!(
+                "3659cfe6000000000000000000000000b528d11cc114e026f138fe568744c6d45ce6da7a"
+            ))
+        );
+        assert_eq!(deposits[2].to, TxKind::Create);
+        assert_eq!(deposits[2].from, BEACON_ROOTS_DEPLOYER);
+        assert_eq!(deposits[3].from, SET_ECOTONE_CALLER);
+        assert_eq!(deposits[3].input, Bytes::from(hex!("22b90ab3")));
+
+        for deposit in deposits {
+            assert_eq!(deposit.mint, U256::ZERO);
+            assert_eq!(deposit.value, U256::ZERO);
+            assert!(!deposit.is_system_transaction);
+        }
+    }
+
+    #[test]
+    fn test_ecotone_upgrade_transactions_unavailable() {
+        assert!(ecotone_upgrade_transactions().is_err());
+    }
+}
@@ -0,0 +1,533 @@
+//! This module contains the [L1BlockInfoTx] type, the L1 info deposit transaction that every L2
+//! block begins with.
+
+use super::{BlockID, DepositSourceDomain, RollupConfig, SystemAccounts, SystemConfig, TxDeposit};
+use alloc::vec::Vec;
+use alloy_consensus::Header;
+use alloy_eips::eip4844::calc_blob_gasprice;
+use alloy_primitives::{Address, Bytes, TxKind, B256, U256};
+use anyhow::{anyhow, bail, Result};
+
+/// The 4 byte selector of the `setL1BlockValues` function of the L1Block contract.
+pub const L1_INFO_TX_SELECTOR_BEDROCK: [u8; 4] = [0x01, 0x5d, 0x8e, 0xb9];
+
+/// The 4 byte selector of the `setL1BlockValuesEcotone` function of the L1Block contract.
+pub const L1_INFO_TX_SELECTOR_ECOTONE: [u8; 4] = [0x44, 0x0a, 0x5e, 0x20];
+
+/// The length of the calldata of a Bedrock L1 info transaction.
+pub const L1_INFO_TX_LEN_BEDROCK: usize = 4 + 32 * 8;
+
+/// The length of the calldata of an Ecotone L1 info transaction.
+pub const L1_INFO_TX_LEN_ECOTONE: usize = 4 + 32 * 5;
+
+/// The gas limit of the L1 info transaction before Regolith.
+pub const L1_INFO_TX_GAS_BEDROCK: u64 = 150_000_000;
+
+/// The gas limit of system transactions after Regolith.
+pub const REGOLITH_SYSTEM_TX_GAS: u64 = 1_000_000;
+
+/// The L1 info transaction, which sets the values of the L1Block predeploy to the attributes of
+/// the L1 origin of an L2 block. Its calldata format depends on the active hardfork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum L1BlockInfoTx {
+    /// A Bedrock `setL1BlockValues` call.
+    Bedrock(L1BlockInfoBedrock),
+    /// An Ecotone `setL1BlockValuesEcotone` call.
+    Ecotone(L1BlockInfoEcotone),
+}
+
+/// The values set by the Bedrock `setL1BlockValues` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct L1BlockInfoBedrock {
+    /// The number of the L1 origin.
+    pub number: u64,
+    /// The timestamp of the L1 origin.
+    pub time: u64,
+    /// The base fee of the L1 origin.
+    pub base_fee: U256,
+    /// The hash of the L1 origin.
+    pub block_hash: B256,
+    /// The sequence number of the L2 block within its epoch.
+    pub sequence_number: u64,
+    /// The address of the batch submitter.
+    pub batcher_address: Address,
+    /// The L1 fee overhead.
+    pub l1_fee_overhead: U256,
+    /// The L1 fee scalar.
+    pub l1_fee_scalar: U256,
+}
+
+/// The values set by the Ecotone `setL1BlockValuesEcotone` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct L1BlockInfoEcotone {
+    /// The number of the L1 origin.
+    pub number: u64,
+    /// The timestamp of the L1 origin.
+    pub time: u64,
+    /// The base fee of the L1 origin.
+    pub base_fee: U256,
+    /// The hash of the L1 origin.
+    pub block_hash: B256,
+    /// The sequence number of the L2 block within its epoch.
+    pub sequence_number: u64,
+    /// The address of the batch submitter.
+    pub batcher_address: Address,
+    /// The blob base fee of the L1 origin.
+    pub blob_base_fee: U256,
+    /// The scalar applied to the blob base fee in the L1 fee.
+    pub blob_base_fee_scalar: u32,
+    /// The scalar applied to the base fee in the L1 fee.
+    pub base_fee_scalar: u32,
+}
+
+impl L1BlockInfoTx {
+    /// Creates the [L1BlockInfoTx] for an L2 block with the given timestamp and sequence number,
+    /// whose L1 origin has the given header.
+    ///
+    /// The Ecotone format is used once Ecotone is active, except in the Ecotone activation block,
+    /// where the L1Block contract has not been upgraded yet.
+    pub fn try_new(
+        rollup_config: &RollupConfig,
+        system_config: &SystemConfig,
+        sequence_number: u64,
+        l1_header: &Header,
+        l1_block_hash: B256,
+        l2_block_time: u64,
+    ) -> Result<Self> {
+        let base_fee = U256::from(l1_header.base_fee_per_gas.unwrap_or_default());
+
+        if !rollup_config.is_ecotone_active(l2_block_time) ||
+            rollup_config.is_ecotone_activation_block(l2_block_time)
+        {
+            return Ok(Self::Bedrock(L1BlockInfoBedrock {
+                number: l1_header.number,
+                time: l1_header.timestamp,
+                base_fee,
+                block_hash: l1_block_hash,
+                sequence_number,
+                batcher_address: system_config.batcher_addr,
+                l1_fee_overhead: system_config.l1_fee_overhead,
+                l1_fee_scalar: system_config.l1_fee_scalar,
+            }));
+        }
+
+        let scalar = system_config.l1_fee_scalar.to_be_bytes::<32>();
+        RollupConfig::check_ecotone_l1_system_config_scalar(scalar)
+            .map_err(|e| anyhow!("Invalid Ecotone L1 fee scalar: {e}"))?;
+        let blob_base_fee_scalar = match scalar[0] {
+            1 => u32::from_be_bytes(scalar[24..28].try_into()?),
+            _ => 0,
+        };
+        let base_fee_scalar = u32::from_be_bytes(scalar[28..32].try_into()?);
+
+        // Before the L1 activates blobs, the blob base fee is the minimum of 1 wei.
+        let blob_base_fee = l1_header.excess_blob_gas.map_or(1, calc_blob_gasprice);
+
+        Ok(Self::Ecotone(L1BlockInfoEcotone {
+            number: l1_header.number,
+            time: l1_header.timestamp,
+            base_fee,
+            block_hash: l1_block_hash,
+            sequence_number,
+            batcher_address: system_config.batcher_addr,
+            blob_base_fee: U256::from(blob_base_fee),
+            blob_base_fee_scalar,
+            base_fee_scalar,
+        }))
+    }
+
+    /// Creates the [L1BlockInfoTx] as in [Self::try_new], along with the deposit transaction that
+    /// carries it.
+    pub fn try_new_with_deposit_tx(
+        rollup_config: &RollupConfig,
+        system_config: &SystemConfig,
+        sequence_number: u64,
+        l1_header: &Header,
+        l1_block_hash: B256,
+        l2_block_time: u64,
+    ) -> Result<(Self, TxDeposit)> {
+        let info = Self::try_new(
+            rollup_config,
+            system_config,
+            sequence_number,
+            l1_header,
+            l1_block_hash,
+            l2_block_time,
+        )?;
+
+        let source = DepositSourceDomain::L1Info { l1_block_hash, seq_number: sequence_number };
+        let accounts = SystemAccounts::default();
+        let mut deposit = TxDeposit {
+            source_hash: source.source_hash(),
+            from: accounts.attributes_depositor,
+            to: TxKind::Call(accounts.attributes_predeploy),
+            mint: U256::ZERO,
+            value: U256::ZERO,
+            gas_limit: L1_INFO_TX_GAS_BEDROCK,
+            is_system_transaction: true,
+            input: info.encode_calldata(),
+        };
+
+        // Regolith removed system transactions, and limits the gas of the L1 info transaction.
+        if rollup_config.is_regolith_active(l2_block_time) {
+            deposit.is_system_transaction = false;
+            deposit.gas_limit = REGOLITH_SYSTEM_TX_GAS;
+        }
+
+        Ok((info, deposit))
+    }
+
+    /// Encodes the calldata of the L1 info transaction.
+    pub fn encode_calldata(&self) -> Bytes {
+        match self {
+            Self::Bedrock(info) => info.encode_calldata(),
+            Self::Ecotone(info) => info.encode_calldata(),
+        }
+    }
+
+    /// Decodes the calldata of an L1 info transaction, in the format given by its selector.
+    pub fn decode_calldata(calldata: &[u8]) -> Result<Self> {
+        match calldata.get(..4) {
+            Some(selector) if selector == L1_INFO_TX_SELECTOR_BEDROCK => {
+                Ok(Self::Bedrock(L1BlockInfoBedrock::decode_calldata(calldata)?))
+            }
+            Some(selector) if selector == L1_INFO_TX_SELECTOR_ECOTONE => {
+                Ok(Self::Ecotone(L1BlockInfoEcotone::decode_calldata(calldata)?))
+            }
+            _ => bail!("Invalid L1 info calldata selector"),
+        }
+    }
+
+    /// Returns the [BlockID] of the L1 origin.
+    pub fn id(&self) -> BlockID {
+        match self {
+            Self::Bedrock(info) => BlockID { hash: info.block_hash, number: info.number },
+            Self::Ecotone(info) => BlockID { hash: info.block_hash, number: info.number },
+        }
+    }
+
+    /// Returns the sequence number of the L2 block within its epoch.
+    pub fn sequence_number(&self) -> u64 {
+        match self {
+            Self::Bedrock(info) => info.sequence_number,
+            Self::Ecotone(info) => info.sequence_number,
+        }
+    }
+
+    /// Returns the base fee of the L1 origin.
+    pub fn base_fee(&self) -> U256 {
+        match self {
+            Self::Bedrock(info) => info.base_fee,
+            Self::Ecotone(info) => info.base_fee,
+        }
+    }
+
+    /// Returns the blob base fee of the L1 origin, which is only set after Ecotone.
+    pub fn blob_base_fee(&self) -> Option<U256> {
+        match self {
+            Self::Bedrock(_) => None,
+            Self::Ecotone(info) => Some(info.blob_base_fee),
+        }
+    }
+
+    /// Returns the address of the batch submitter.
+    pub fn batcher_address(&self) -> Address {
+        match self {
+            Self::Bedrock(info) => info.batcher_address,
+            Self::Ecotone(info) => info.batcher_address,
+        }
+    }
+}
+
+impl L1BlockInfoBedrock {
+    /// Encodes the calldata of the `setL1BlockValues` call.
+    ///
+    /// ```text
+    /// function setL1BlockValues(
+    ///    uint64 _number,
+    ///    uint64 _timestamp,
+    ///    uint256 _basefee,
+    ///    bytes32 _hash,
+    ///    uint64 _sequenceNumber,
+    ///    bytes32 _batcherHash,
+    ///    uint256 _l1FeeOverhead,
+    ///    uint256 _l1FeeScalar
+    /// )
+    /// ```
+    pub fn encode_calldata(&self) -> Bytes {
+        let mut buf = Vec::with_capacity(L1_INFO_TX_LEN_BEDROCK);
+        buf.extend_from_slice(&L1_INFO_TX_SELECTOR_BEDROCK);
+        buf.extend_from_slice(U256::from(self.number).to_be_bytes::<32>().as_slice());
+        buf.extend_from_slice(U256::from(self.time).to_be_bytes::<32>().as_slice());
+        buf.extend_from_slice(self.base_fee.to_be_bytes::<32>().as_slice());
+        buf.extend_from_slice(self.block_hash.as_slice());
+        buf.extend_from_slice(U256::from(self.sequence_number).to_be_bytes::<32>().as_slice());
+        buf.extend_from_slice(self.batcher_address.into_word().as_slice());
+        buf.extend_from_slice(self.l1_fee_overhead.to_be_bytes::<32>().as_slice());
+        buf.extend_from_slice(self.l1_fee_scalar.to_be_bytes::<32>().as_slice());
+        buf.into()
+    }
+
+    /// Decodes the calldata of a `setL1BlockValues` call. See [Self::encode_calldata].
+    pub fn decode_calldata(calldata: &[u8]) -> Result<Self> {
+        check_calldata(calldata, L1_INFO_TX_SELECTOR_BEDROCK, L1_INFO_TX_LEN_BEDROCK)?;
+        let word = |i: usize| &calldata[4 + 32 * i..4 + 32 * (i + 1)];
+
+        Ok(Self {
+            number: read_u64_word(word(0))?,
+            time: read_u64_word(word(1))?,
+            base_fee: U256::from_be_slice(word(2)),
+            block_hash: B256::from_slice(word(3)),
+            sequence_number: read_u64_word(word(4))?,
+            batcher_address: read_address_word(word(5))?,
+            l1_fee_overhead: U256::from_be_slice(word(6)),
+            l1_fee_scalar: U256::from_be_slice(word(7)),
+        })
+    }
+}
+
+impl L1BlockInfoEcotone {
+    /// Encodes the calldata of the `setL1BlockValuesEcotone` call. Unlike the Bedrock call, the
+    /// arguments are tightly packed rather than ABI encoded.
+    ///
+    /// ```text
+    /// function setL1BlockValuesEcotone(
+    ///    uint32 _baseFeeScalar,
+    ///    uint32 _blobBaseFeeScalar,
+    ///    uint64 _sequenceNumber,
+    ///    uint64 _timestamp,
+    ///    uint64 _number,
+    ///    uint256 _basefee,
+    ///    uint256 _blobBaseFee,
+    ///    bytes32 _hash,
+    ///    bytes32 _batcherHash
+    /// )
+    /// ```
+    pub fn encode_calldata(&self) -> Bytes {
+        let mut buf = Vec::with_capacity(L1_INFO_TX_LEN_ECOTONE);
+        buf.extend_from_slice(&L1_INFO_TX_SELECTOR_ECOTONE);
+        buf.extend_from_slice(&self.base_fee_scalar.to_be_bytes());
+        buf.extend_from_slice(&self.blob_base_fee_scalar.to_be_bytes());
+        buf.extend_from_slice(&self.sequence_number.to_be_bytes());
+        buf.extend_from_slice(&self.time.to_be_bytes());
+        buf.extend_from_slice(&self.number.to_be_bytes());
+        buf.extend_from_slice(self.base_fee.to_be_bytes::<32>().as_slice());
+        buf.extend_from_slice(self.blob_base_fee.to_be_bytes::<32>().as_slice());
+        buf.extend_from_slice(self.block_hash.as_slice());
+        buf.extend_from_slice(self.batcher_address.into_word().as_slice());
+        buf.into()
+    }
+
+    /// Decodes the calldata of a `setL1BlockValuesEcotone` call. See [Self::encode_calldata].
+    pub fn decode_calldata(calldata: &[u8]) -> Result<Self> {
+        check_calldata(calldata, L1_INFO_TX_SELECTOR_ECOTONE, L1_INFO_TX_LEN_ECOTONE)?;
+        let u32_at =
+            |i: usize| -> Result<u32> { Ok(u32::from_be_bytes(calldata[i..i + 4].try_into()?)) };
+        let u64_at =
+            |i: usize| -> Result<u64> { Ok(u64::from_be_bytes(calldata[i..i + 8].try_into()?)) };
+
+        Ok(Self {
+            base_fee_scalar: u32_at(4)?,
+            blob_base_fee_scalar: u32_at(8)?,
+            sequence_number: u64_at(12)?,
+            time: u64_at(20)?,
+            number: u64_at(28)?,
+            base_fee: U256::from_be_slice(&calldata[36..68]),
+            blob_base_fee: U256::from_be_slice(&calldata[68..100]),
+            block_hash: B256::from_slice(&calldata[100..132]),
+            batcher_address: read_address_word(&calldata[132..164])?,
+        })
+    }
+}
+
+/// Returns an error if `calldata` is not `len` bytes long or does not begin with `selector`.
+fn check_calldata(calldata: &[u8], selector: [u8; 4], len: usize) -> Result<()> {
+    if calldata.len() != len {
+        bail!("Invalid L1 info calldata length {}, expected {len}", calldata.len());
+    }
+    if calldata[..4] != selector {
+        bail!("Invalid L1 info calldata selector {:?}", &calldata[..4]);
+    }
+    Ok(())
+}
+
+/// Reads a `uint64` from an ABI-encoded 32 byte word, whose padding must be zero.
+fn read_u64_word(word: &[u8]) -> Result<u64> {
+    if word[..24].iter().any(|b| *b != 0) {
+        bail!("Invalid uint64 padding in L1 info calldata");
+    }
+    Ok(u64::from_be_bytes(word[24..].try_into()?))
+}
+
+/// Reads an `address` from a 32 byte word, whose padding must be zero.
+fn read_address_word(word: &[u8]) -> Result<Address> {
+    if word[..12].iter().any(|b| *b != 0) {
+        bail!("Invalid address padding in L1 info calldata");
+    }
+    Ok(Address::from_slice(&word[12..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256, hex};
+
+    fn ecotone_config() -> RollupConfig {
+        RollupConfig {
+            block_time: 2,
+            regolith_time: Some(0),
+            ecotone_time: Some(10),
+            ..Default::default()
+        }
+    }
+
+    fn l1_header() -> Header {
+        Header {
+            number: 100,
+            timestamp: 1_000,
+            base_fee_per_gas: Some(7),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bedrock_calldata() {
+        let system_config = SystemConfig {
+            batcher_addr: address!("6887246668a3b87f54deb3b94ba47a6f63f32985"),
+            l1_fee_overhead: U256::from(0xbc),
+            l1_fee_scalar: U256::from(0xa6fe0),
+            ..Default::default()
+        };
+        let hash = b256!("392012032675be9f94aae5ab442de73c5f4fb1bf30fa7dd0d2442239899a40fc");
+        let (info, deposit) = L1BlockInfoTx::try_new_with_deposit_tx(
+            &ecotone_config(),
+            &system_config,
+            4,
+            &l1_header(),
+            hash,
+            8,
+        )
+        .unwrap();
+
+        let calldata = info.encode_calldata();
+        check_calldata(&calldata, L1_INFO_TX_SELECTOR_BEDROCK, L1_INFO_TX_LEN_BEDROCK).unwrap();
+        assert_eq!(calldata[4 + 31], 100);
+        assert_eq!(calldata[4 + 32 * 3..4 + 32 * 4], hash);
+        assert_eq!(calldata[4 + 32 * 5 + 12..4 + 32 * 6], system_config.batcher_addr);
+
+        assert_eq!(deposit.input, calldata);
+        assert_eq!(deposit.gas_limit, REGOLITH_SYSTEM_TX_GAS);
+        assert!(!deposit.is_system_transaction);
+        assert_eq!(
+            deposit.source_hash,
+            DepositSourceDomain::L1Info { l1_block_hash: hash, seq_number: 4 }.source_hash()
+        );
+    }
+
+    #[test]
+    fn test_ecotone_activation_block_is_bedrock() {
+        let cfg = ecotone_config();
+        let system_config = SystemConfig::default();
+        let info =
+            L1BlockInfoTx::try_new(&cfg, &system_config, 0, &l1_header(), B256::ZERO, 10).unwrap();
+        assert!(matches!(info, L1BlockInfoTx::Bedrock(_)));
+        let info =
+            L1BlockInfoTx::try_new(&cfg, &system_config, 0, &l1_header(), B256::ZERO, 12).unwrap();
+        assert!(matches!(info, L1BlockInfoTx::Ecotone(_)));
+    }
+
+    #[test]
+    fn test_ecotone_calldata() {
+        let mut scalar = [0u8; 32];
+        scalar[0] = 1;
+        scalar[24..28].copy_from_slice(&0x0101_0101u32.to_be_bytes());
+        scalar[28..32].copy_from_slice(&0x0202_0202u32.to_be_bytes());
+        let system_config = SystemConfig {
+            batcher_addr: address!("6887246668a3b87f54deb3b94ba47a6f63f32985"),
+            l1_fee_scalar: U256::from_be_bytes(scalar),
+            ..Default::default()
+        };
+        let info = L1BlockInfoTx::try_new(
+            &ecotone_config(),
+            &system_config,
+            3,
+            &l1_header(),
+            B256::repeat_byte(0xAB),
+            12,
+        )
+        .unwrap();
+        let L1BlockInfoTx::Ecotone(ecotone) = info else { panic!("expected Ecotone info") };
+        assert_eq!(ecotone.blob_base_fee, U256::from(1));
+        assert_eq!(ecotone.blob_base_fee_scalar, 0x0101_0101);
+        assert_eq!(ecotone.base_fee_scalar, 0x0202_0202);
+
+        let calldata = info.encode_calldata();
+        check_calldata(&calldata, L1_INFO_TX_SELECTOR_ECOTONE, L1_INFO_TX_LEN_ECOTONE).unwrap();
+        assert_eq!(
+            calldata[..36],
+            hex!("440a5e200202020201010101000000000000000300000000000003e80000000000000064")[..]
+        );
+    }
+
+    #[test]
+    fn test_decode_calldata_roundtrip() {
+        let bedrock = L1BlockInfoTx::Bedrock(L1BlockInfoBedrock {
+            number: 1,
+            time: 2,
+            base_fee: U256::from(3),
+            block_hash: B256::repeat_byte(4),
+            sequence_number: 5,
+            batcher_address: Address::repeat_byte(6),
+            l1_fee_overhead: U256::from(7),
+            l1_fee_scalar: U256::from(8),
+        });
+        let decoded = L1BlockInfoTx::decode_calldata(&bedrock.encode_calldata()).unwrap();
+        assert_eq!(decoded, bedrock);
+        assert_eq!(decoded.id(), BlockID { hash: B256::repeat_byte(4), number: 1 });
+        assert_eq!(decoded.sequence_number(), 5);
+        assert_eq!(decoded.blob_base_fee(), None);
+
+        let ecotone = L1BlockInfoTx::Ecotone(L1BlockInfoEcotone {
+            number: 1,
+            time: 2,
+            base_fee: U256::from(3),
+            block_hash: B256::repeat_byte(4),
+            sequence_number: 5,
+            batcher_address: Address::repeat_byte(6),
+            blob_base_fee: U256::from(7),
+            blob_base_fee_scalar: 8,
+            base_fee_scalar: 9,
+        });
+        let decoded = L1BlockInfoTx::decode_calldata(&ecotone.encode_calldata()).unwrap();
+        assert_eq!(decoded, ecotone);
+        assert_eq!(decoded.blob_base_fee(), Some(U256::from(7)));
+        assert_eq!(decoded.batcher_address(), Address::repeat_byte(6));
+    }
+
+    #[test]
+    fn test_decode_calldata_invalid() {
+        let calldata = L1BlockInfoTx::Bedrock(L1BlockInfoBedrock::default()).encode_calldata();
+        assert!(L1BlockInfoTx::decode_calldata(&calldata[..calldata.len() - 1]).is_err());
+        assert!(L1BlockInfoTx::decode_calldata(&[0u8; L1_INFO_TX_LEN_BEDROCK]).is_err());
+        assert!(L1BlockInfoTx::decode_calldata(&[]).is_err());
+
+        // The number must fit in a uint64.
+        let mut calldata = calldata.to_vec();
+        calldata[4] = 1;
+        assert!(L1BlockInfoBedrock::decode_calldata(&calldata).is_err());
+    }
+
+    #[test]
+    fn test_ecotone_invalid_scalar() {
+        let mut scalar = [0u8; 32];
+        scalar[0] = 1;
+        scalar[1] = 1;
+        let system_config =
+            SystemConfig { l1_fee_scalar: U256::from_be_bytes(scalar), ..Default::default() };
+        let cfg = ecotone_config();
+        assert!(
+            L1BlockInfoTx::try_new(&cfg, &system_config, 0, &l1_header(), B256::ZERO, 12).is_err()
+        );
+    }
+}
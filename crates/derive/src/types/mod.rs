@@ -27,7 +27,8 @@ pub use batch::{
 
 mod payload;
 pub use payload::{
-    ExecutionPayload, ExecutionPayloadEnvelope, PAYLOAD_MEM_FIXED_COST, PAYLOAD_TX_MEM_OVERHEAD,
+    ExecutionPayload, ExecutionPayloadEnvelope, Withdrawals, PAYLOAD_MEM_FIXED_COST,
+    PAYLOAD_TX_MEM_OVERHEAD,
 };
 
 mod block;
@@ -45,6 +46,31 @@ pub use frame::Frame;
 mod channel;
 pub use channel::Channel;
 
+mod deposits;
+pub use deposits::{
+    decode_deposit, deposits_from_receipts, derive_deposits, DepositSourceDomain, TxDeposit,
+    DEPOSIT_EVENT_VERSION_0, DEPOSIT_TX_TYPE,
+};
+
+mod ecotone;
+pub use ecotone::{
+    ecotone_upgrade_transactions, BEACON_ROOTS_ADDRESS, BEACON_ROOTS_DEPLOYER,
+    BEACON_ROOTS_DEPLOYMENT_BYTECODE, ECOTONE_BEACON_ROOTS_DEPLOYMENT_SOURCE_HASH,
+    ECOTONE_ENABLE_GAS_PRICE_ORACLE_SOURCE_HASH, ECOTONE_GAS_PRICE_ORACLE_ADDRESS,
+    ECOTONE_GAS_PRICE_ORACLE_DEPLOYER, ECOTONE_GAS_PRICE_ORACLE_DEPLOYMENT_SOURCE_HASH,
+    ECOTONE_GAS_PRICE_ORACLE_PROXY_UPDATE_SOURCE_HASH, ECOTONE_L1_BLOCK_ADDRESS,
+    ECOTONE_L1_BLOCK_DEPLOYER, ECOTONE_L1_BLOCK_DEPLOYMENT_SOURCE_HASH,
+    ECOTONE_L1_BLOCK_PROXY_UPDATE_SOURCE_HASH, GAS_PRICE_ORACLE_PROXY, L1_BLOCK_PROXY,
+    SET_ECOTONE_CALLER, SET_ECOTONE_SELECTOR, UPGRADE_TO_SELECTOR,
+};
+
+mod l1_block_info;
+pub use l1_block_info::{
+    L1BlockInfoBedrock, L1BlockInfoEcotone, L1BlockInfoTx, L1_INFO_TX_GAS_BEDROCK,
+    L1_INFO_TX_LEN_BEDROCK, L1_INFO_TX_LEN_ECOTONE, L1_INFO_TX_SELECTOR_BEDROCK,
+    L1_INFO_TX_SELECTOR_ECOTONE, REGOLITH_SYSTEM_TX_GAS,
+};
+
 mod errors;
 pub use errors::*;
 
@@ -61,7 +87,7 @@ impl RawTransaction {
 
     /// Returns if the transaction is a deposit
     pub fn is_deposit(&self) -> bool {
-        !self.0.is_empty() && self.0[0] == 0x7E
+        !self.0.is_empty() && self.0[0] == DEPOSIT_TX_TYPE
     }
 }
 
@@ -1,7 +1,9 @@
 //! Contains the execution payload type.
 
+use super::{BlockInfo, Genesis, L1BlockInfoTx, L2BlockInfo, TxDeposit};
 use alloc::vec::Vec;
 use alloy_primitives::{Address, Bytes, B256, U256};
+use anyhow::{anyhow, bail, Result};
 
 /// Fixed and variable memory costs for a payload.
 /// ~1000 bytes per payload, with some margin for overhead like map data.
@@ -39,7 +41,7 @@ impl ExecutionPayloadEnvelope {
 
 /// The execution payload.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ExecutionPayload {
     /// The parent hash.
     #[cfg_attr(feature = "serde", serde(rename = "parentHash"))]
@@ -94,7 +96,109 @@ pub struct ExecutionPayload {
     pub excess_blob_gas: Option<u64>,
 }
 
+impl ExecutionPayload {
+    /// Returns the [L2BlockInfo] of the payload's block. Its L1 origin and sequence number are
+    /// recovered from the L1 info deposit that every L2 block begins with, except for the genesis
+    /// block, which takes its L1 origin from the [Genesis].
+    ///
+    /// # Returns
+    /// - `Ok(L2BlockInfo)` with the block info of the payload.
+    /// - `Err(_)` if the payload is not the genesis block, and does not begin with a valid L1 info
+    ///   deposit.
+    pub fn to_l2_block_info(&self, genesis: &Genesis) -> Result<L2BlockInfo> {
+        let block_info =
+            BlockInfo::new(self.block_hash, self.block_number, self.parent_hash, self.timestamp);
+
+        if self.block_number == genesis.l2.number {
+            if self.block_hash != genesis.l2.hash {
+                bail!(
+                    "Expected L2 genesis hash {}, got {} at block {}",
+                    genesis.l2.hash,
+                    self.block_hash,
+                    self.block_number
+                );
+            }
+            return Ok(L2BlockInfo::new(block_info, genesis.l1, 0));
+        }
+
+        let Some(first_tx) = self.transactions.first() else {
+            bail!("L2 block {} is missing the L1 info deposit", self.block_number);
+        };
+        let deposit = TxDeposit::decode_2718(&mut first_tx.as_ref()).map_err(|e| {
+            anyhow!("First transaction of L2 block {} is not a deposit: {e}", self.block_number)
+        })?;
+        let l1_info = L1BlockInfoTx::decode_calldata(&deposit.input)?;
+        Ok(L2BlockInfo::new(block_info, l1_info.id(), l1_info.sequence_number()))
+    }
+}
+
 /// Withdrawal Type
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Withdrawals {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BlockID, L1BlockInfoBedrock, RawTransaction};
+    use alloc::vec;
+
+    fn genesis() -> Genesis {
+        Genesis {
+            l1: BlockID { hash: B256::repeat_byte(0x01), number: 10 },
+            l2: BlockID { hash: B256::repeat_byte(0x02), number: 0 },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_to_l2_block_info_genesis() {
+        let payload = ExecutionPayload { block_hash: genesis().l2.hash, ..Default::default() };
+        let info = payload.to_l2_block_info(&genesis()).unwrap();
+        assert_eq!(info.l1_origin, genesis().l1);
+        assert_eq!(info.seq_num, 0);
+
+        let payload = ExecutionPayload { block_hash: B256::ZERO, ..Default::default() };
+        assert!(payload.to_l2_block_info(&genesis()).is_err());
+    }
+
+    #[test]
+    fn test_to_l2_block_info() {
+        let l1_info = L1BlockInfoTx::Bedrock(L1BlockInfoBedrock {
+            number: 12,
+            block_hash: B256::repeat_byte(0x0C),
+            sequence_number: 3,
+            ..Default::default()
+        });
+        let deposit = TxDeposit { input: l1_info.encode_calldata(), ..Default::default() };
+        let payload = ExecutionPayload {
+            parent_hash: B256::repeat_byte(0xAA),
+            block_number: 5,
+            timestamp: 10,
+            block_hash: B256::repeat_byte(0xBB),
+            transactions: vec![RawTransaction::from(deposit).0],
+            ..Default::default()
+        };
+
+        let info = payload.to_l2_block_info(&genesis()).unwrap();
+        assert_eq!(
+            info.block_info,
+            BlockInfo::new(B256::repeat_byte(0xBB), 5, B256::repeat_byte(0xAA), 10)
+        );
+        assert_eq!(info.l1_origin, BlockID { hash: B256::repeat_byte(0x0C), number: 12 });
+        assert_eq!(info.seq_num, 3);
+    }
+
+    #[test]
+    fn test_to_l2_block_info_missing_l1_info() {
+        let payload = ExecutionPayload { block_number: 5, ..Default::default() };
+        assert!(payload.to_l2_block_info(&genesis()).is_err());
+
+        let payload = ExecutionPayload {
+            block_number: 5,
+            transactions: vec![Bytes::from_static(&[0x02, 0xC0])],
+            ..Default::default()
+        };
+        assert!(payload.to_l2_block_info(&genesis()).is_err());
+    }
+}
@@ -104,6 +104,14 @@ impl RollupConfig {
         self.ecotone_time.map_or(false, |t| timestamp >= t)
     }
 
+    /// Returns true if the block at the given timestamp is the first block in which Ecotone is
+    /// active.
+    pub fn is_ecotone_activation_block(&self, timestamp: u64) -> bool {
+        self.is_ecotone_active(timestamp) &&
+            timestamp >= self.block_time &&
+            !self.is_ecotone_active(timestamp - self.block_time)
+    }
+
     /// Returns true if Fjord is active at the given timestamp.
     pub fn is_fjord_active(&self, timestamp: u64) -> bool {
         self.fjord_time.map_or(false, |t| timestamp >= t)
@@ -0,0 +1,307 @@
+//! This module contains async versions of the host-side [crate::OracleServer] and
+//! [crate::HintReader], which serve the preimage and hint protocols over any
+//! [tokio::io::AsyncRead] + [tokio::io::AsyncWrite] transport, such as pipes, Unix sockets, or the
+//! stdio of a child process.
+//!
+//! Unlike the synchronous servers, which block a thread per pipe, a single host process can drive
+//! many async servers concurrently, and fetch preimages for hints concurrently.
+
+use crate::PreimageKey;
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use core::fmt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// An [AsyncPreimageFetcher] is a pluggable, async source of preimages for the host. A single
+/// fetcher may be shared between several [AsyncOracleServer]s that run concurrently.
+#[async_trait]
+pub trait AsyncPreimageFetcher {
+    /// Fetch the preimage for the given [PreimageKey].
+    async fn get_preimage(&self, key: PreimageKey) -> Result<Vec<u8>>;
+}
+
+/// An [AsyncHintRouter] is an async destination for hints received from the client. A single
+/// router may be shared between several [AsyncHintReader]s that run concurrently.
+#[async_trait]
+pub trait AsyncHintRouter {
+    /// Route a hint to the appropriate handler. The hint is not acknowledged to the client until
+    /// the returned future resolves.
+    async fn route_hint(&self, hint: String) -> Result<()>;
+}
+
+/// An [AsyncOracleServer] serves preimage requests from a client [crate::OracleReader] over an
+/// async transport.
+#[derive(Debug)]
+pub struct AsyncOracleServer<R, W> {
+    reader: R,
+    writer: W,
+}
+
+impl<R, W> AsyncOracleServer<R, W>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+{
+    /// Create a new [AsyncOracleServer] from the read and write halves of a transport. The reader
+    /// receives preimage requests from the client, and the writer sends preimages back.
+    pub fn new(reader: R, writer: W) -> Self {
+        Self { reader, writer }
+    }
+
+    /// Serve the next preimage request from the client with the passed [AsyncPreimageFetcher].
+    pub async fn next_preimage_request<F>(&mut self, fetcher: &F) -> Result<()>
+    where
+        F: AsyncPreimageFetcher + Sync + ?Sized,
+    {
+        // Read the preimage request from the client.
+        let mut buf = [0u8; 32];
+        read_frame_start(&mut self.reader, &mut buf).await?;
+        let preimage_key = PreimageKey::try_from(buf).map_err(|e| anyhow!(e))?;
+
+        // Fetch the preimage value from the preimage getter.
+        let value = fetcher.get_preimage(preimage_key).await?;
+
+        // Write the length as a big-endian u64 followed by the data.
+        let mut data = Vec::with_capacity(value.len() + 8);
+        data.extend_from_slice(&(value.len() as u64).to_be_bytes());
+        data.extend_from_slice(&value);
+        self.writer.write_all(&data).await?;
+        self.writer.flush().await?;
+
+        Ok(())
+    }
+
+    /// Serve preimage requests until the client closes the transport.
+    pub async fn serve<F>(&mut self, fetcher: &F) -> Result<()>
+    where
+        F: AsyncPreimageFetcher + Sync + ?Sized,
+    {
+        loop {
+            if let Err(e) = self.next_preimage_request(fetcher).await {
+                return if is_closed(&e) { Ok(()) } else { Err(e) };
+            }
+        }
+    }
+}
+
+/// An [AsyncHintReader] reads hints from a client [crate::HintWriter] over an async transport,
+/// and routes them to an [AsyncHintRouter].
+#[derive(Debug)]
+pub struct AsyncHintReader<R, W> {
+    reader: R,
+    writer: W,
+}
+
+impl<R, W> AsyncHintReader<R, W>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+{
+    /// Create a new [AsyncHintReader] from the read and write halves of a transport. The reader
+    /// receives hints from the client, and the writer sends acknowledgements back.
+    pub fn new(reader: R, writer: W) -> Self {
+        Self { reader, writer }
+    }
+
+    /// Read the next hint from the client, route it, and acknowledge it. The acknowledgement is
+    /// always sent once routing has finished so that the client is never left blocked, even if the
+    /// hint could not be handled.
+    pub async fn next_hint<H>(&mut self, router: &H) -> Result<()>
+    where
+        H: AsyncHintRouter + Sync + ?Sized,
+    {
+        // Read the length of the raw hint payload.
+        let mut len_buf = [0u8; 4];
+        read_frame_start(&mut self.reader, &mut len_buf).await?;
+        let len = u32::from_be_bytes(len_buf);
+
+        // Read the raw hint payload.
+        let mut raw_payload = vec![0u8; len as usize];
+        self.reader.read_exact(raw_payload.as_mut_slice()).await?;
+        let payload = String::from_utf8(raw_payload)
+            .map_err(|e| anyhow!("Failed to decode hint payload: {e}"))?;
+
+        // Route the hint, and acknowledge it once the handler has finished.
+        let result = router.route_hint(payload).await;
+        self.writer.write_all(&[0x00]).await?;
+        self.writer.flush().await?;
+
+        result
+    }
+
+    /// Read and route hints until the client closes the transport.
+    pub async fn serve<H>(&mut self, router: &H) -> Result<()>
+    where
+        H: AsyncHintRouter + Sync + ?Sized,
+    {
+        loop {
+            if let Err(e) = self.next_hint(router).await {
+                return if is_closed(&e) { Ok(()) } else { Err(e) };
+            }
+        }
+    }
+}
+
+/// Reads the first `buf.len()` bytes of a new frame. If the transport is closed before any byte of
+/// the frame arrives, a [TransportClosed] error is returned. A transport closed partway through
+/// the frame is reported as an I/O error.
+async fn read_frame_start<R>(reader: &mut R, buf: &mut [u8]) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    let n = reader.read(buf).await?;
+    if n == 0 {
+        return Err(anyhow!(TransportClosed));
+    }
+    reader.read_exact(&mut buf[n..]).await?;
+    Ok(())
+}
+
+/// The error returned when the client closes the transport at a frame boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TransportClosed;
+
+impl fmt::Display for TransportClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Client closed the transport")
+    }
+}
+
+impl std::error::Error for TransportClosed {}
+
+/// Returns `true` if the error was caused by the client closing the transport between frames.
+fn is_closed(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<TransportClosed>().is_some()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PreimageKeyType;
+    use alloc::{string::ToString, sync::Arc};
+    use std::sync::Mutex;
+    use tokio::io::{duplex, split};
+
+    /// An [AsyncPreimageFetcher] that returns the rendered key as the preimage.
+    struct EchoFetcher;
+
+    #[async_trait]
+    impl AsyncPreimageFetcher for EchoFetcher {
+        async fn get_preimage(&self, key: PreimageKey) -> Result<Vec<u8>> {
+            Ok(<[u8; 32]>::from(key).to_vec())
+        }
+    }
+
+    /// An [AsyncHintRouter] that records every hint it receives.
+    #[derive(Default)]
+    struct RecordingRouter(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl AsyncHintRouter for RecordingRouter {
+        async fn route_hint(&self, hint: String) -> Result<()> {
+            self.0.lock().unwrap().push(hint);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_oracle_server_concurrent_clients() {
+        let fetcher = Arc::new(EchoFetcher);
+
+        let mut clients = Vec::new();
+        for i in 0..4u8 {
+            let (client, host) = duplex(64);
+            let fetcher = fetcher.clone();
+            let server = tokio::spawn(async move {
+                let (reader, writer) = split(host);
+                AsyncOracleServer::new(reader, writer).serve(fetcher.as_ref()).await
+            });
+
+            clients.push(tokio::spawn(async move {
+                let (mut reader, mut writer) = split(client);
+                for j in 0..3u8 {
+                    let key: [u8; 32] =
+                        PreimageKey::new([i ^ j; 32], PreimageKeyType::Keccak256).into();
+                    writer.write_all(&key).await.unwrap();
+
+                    let mut len = [0u8; 8];
+                    reader.read_exact(&mut len).await.unwrap();
+                    let mut data = vec![0u8; u64::from_be_bytes(len) as usize];
+                    reader.read_exact(&mut data).await.unwrap();
+                    assert_eq!(data, key);
+                }
+                drop((reader, writer));
+                server.await.unwrap().unwrap();
+            }));
+        }
+
+        for client in clients {
+            client.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_oracle_server_rejects_invalid_key() {
+        let (mut client, host) = duplex(64);
+        let (reader, writer) = split(host);
+        let mut server = AsyncOracleServer::new(reader, writer);
+
+        client.write_all(&[0xFF; 32]).await.unwrap();
+        assert!(server.next_preimage_request(&EchoFetcher).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_async_oracle_server_partial_frame() {
+        let (mut client, host) = duplex(64);
+        let (reader, writer) = split(host);
+        let mut server = AsyncOracleServer::new(reader, writer);
+
+        // A key cut off by the client closing the transport is an error, not a clean close.
+        client.write_all(&[0x02; 16]).await.unwrap();
+        drop(client);
+        let err = server.serve(&EchoFetcher).await.unwrap_err();
+        assert!(!is_closed(&err));
+    }
+
+    #[tokio::test]
+    async fn test_async_hint_reader_partial_frame() {
+        let router = RecordingRouter::default();
+        let (mut client, host) = duplex(64);
+        let (reader, writer) = split(host);
+        let mut hint_reader = AsyncHintReader::new(reader, writer);
+
+        // A hint shorter than its length prefix is an error, not a clean close.
+        client.write_all(&8u32.to_be_bytes()).await.unwrap();
+        client.write_all(b"l2-").await.unwrap();
+        drop(client);
+        assert!(hint_reader.serve(&router).await.is_err());
+        assert!(router.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_async_hint_reader() {
+        let router = RecordingRouter::default();
+        let (mut client, host) = duplex(64);
+        let (reader, writer) = split(host);
+        let mut hint_reader = AsyncHintReader::new(reader, writer);
+
+        let hints = ["l1-block-header 0xaa", "l2-output 0xbb"];
+        let client_task = tokio::spawn(async move {
+            for hint in hints {
+                client.write_all(&(hint.len() as u32).to_be_bytes()).await.unwrap();
+                client.write_all(hint.as_bytes()).await.unwrap();
+                let mut ack = [0xFFu8; 1];
+                client.read_exact(&mut ack).await.unwrap();
+                assert_eq!(ack, [0x00]);
+            }
+        });
+
+        hint_reader.serve(&router).await.unwrap();
+        client_task.await.unwrap();
+        assert_eq!(
+            *router.0.lock().unwrap(),
+            hints.iter().map(ToString::to_string).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Contains utilities for fetching EIP-4844 blobs from the preimage oracle.
+//!
+//! Blobs are served by the host one field element at a time. Each field element is keyed by
+//! `keccak256(commitment ++ z)`, where `z` is the root of unity that the blob polynomial is
+//! evaluated at for that field element, with the type byte set to [PreimageKeyType::Blob].
+//!
+//! [PreimageKeyType::Blob]: crate::PreimageKeyType::Blob
+
+use crate::{PreimageKey, PreimageOracleClient};
+use alloc::{boxed::Box, vec};
+use anyhow::Result;
+
+/// The number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+
+/// The size of a blob, in bytes.
+pub const BLOB_SIZE: usize = FIELD_ELEMENTS_PER_BLOB * 32;
+
+/// An EIP-4844 blob, as the concatenation of its field elements.
+pub type Blob = [u8; BLOB_SIZE];
+
+/// The number of bits in a field element index.
+const FIELD_ELEMENT_INDEX_BITS: u32 = FIELD_ELEMENTS_PER_BLOB.trailing_zeros();
+
+/// The BLS12-381 scalar field modulus, as little-endian limbs.
+const MODULUS: [u64; 4] =
+    [0xffffffff00000001, 0x53bda402fffe5bfe, 0x3339d80809a1d805, 0x73eda753299d7d48];
+
+/// `-MODULUS^{-1} mod 2^64`, used for Montgomery reduction.
+const INV: u64 = 0xfffffffeffffffff;
+
+/// `1` in Montgomery form (`2^256 mod MODULUS`).
+const ONE: [u64; 4] =
+    [0x00000001fffffffe, 0x5884b7fa00034802, 0x998c4fefecbc4ff5, 0x1824b159acc5056f];
+
+/// The primitive 4096th root of unity, `7^((MODULUS - 1) / 4096)`, in Montgomery form.
+const ROOT_OF_UNITY: [u64; 4] =
+    [0xf2df262c09458a39, 0x048cdf5b99dff177, 0x16857bc5c7cce57b, 0x043b3dbca4a915ae];
+
+/// Returns the root of unity that the field element at `index` of a blob is evaluated at, as a
+/// 32-byte big-endian scalar. Blob field elements are ordered by the bit-reversal permutation of
+/// the roots of unity, so the field element at `index` is evaluated at `ω^bit_reverse(index)`.
+///
+/// # Panics
+/// Panics if `index >= FIELD_ELEMENTS_PER_BLOB`.
+pub fn root_of_unity(index: usize) -> [u8; 32] {
+    assert!(index < FIELD_ELEMENTS_PER_BLOB, "Field element index out of bounds: {index}");
+
+    let exponent = bit_reverse(index);
+    let mut acc = ONE;
+    for bit in (0..FIELD_ELEMENT_INDEX_BITS).rev() {
+        acc = mont_mul(&acc, &acc);
+        if exponent >> bit & 1 == 1 {
+            acc = mont_mul(&acc, &ROOT_OF_UNITY);
+        }
+    }
+    to_be_bytes(&acc)
+}
+
+/// Fetches the blob with the given KZG commitment from the oracle, one field element at a time.
+///
+/// The blob is assembled in place on the heap, as it is too large to be moved around on the stack.
+/// The host must be able to serve the blob's field elements, which is usually ensured by sending an
+/// `l1-blob` hint beforehand.
+pub fn get_blob<O>(oracle: &mut O, commitment: &[u8; 48]) -> Result<Box<Blob>>
+where
+    O: PreimageOracleClient + ?Sized,
+{
+    let mut blob: Box<Blob> =
+        vec![0u8; BLOB_SIZE].into_boxed_slice().try_into().expect("Blob size is constant");
+
+    // Walk the roots of unity in their natural order, placing each field element at its
+    // bit-reversed position in the blob.
+    let mut root = ONE;
+    for i in 0..FIELD_ELEMENTS_PER_BLOB {
+        let index = bit_reverse(i);
+        let key = PreimageKey::new_blob(commitment, &to_be_bytes(&root));
+        oracle.get_exact(key, &mut blob[index << 5..(index + 1) << 5])?;
+        root = mont_mul(&root, &ROOT_OF_UNITY);
+    }
+
+    Ok(blob)
+}
+
+/// Reverses the low [FIELD_ELEMENT_INDEX_BITS] bits of `index`.
+fn bit_reverse(index: usize) -> usize {
+    index.reverse_bits() >> (usize::BITS - FIELD_ELEMENT_INDEX_BITS)
+}
+
+/// Montgomery multiplication of two scalars over [MODULUS].
+fn mont_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 6];
+    for b_i in b {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let sum = t[j] as u128 + a[j] as u128 * *b_i as u128 + carry;
+            t[j] = sum as u64;
+            carry = sum >> 64;
+        }
+        let sum = t[4] as u128 + carry;
+        t[4] = sum as u64;
+        t[5] = (sum >> 64) as u64;
+
+        let m = t[0].wrapping_mul(INV);
+        let mut carry = (t[0] as u128 + m as u128 * MODULUS[0] as u128) >> 64;
+        for j in 1..4 {
+            let sum = t[j] as u128 + m as u128 * MODULUS[j] as u128 + carry;
+            t[j - 1] = sum as u64;
+            carry = sum >> 64;
+        }
+        let sum = t[4] as u128 + carry;
+        t[3] = sum as u64;
+        t[4] = t[5] + (sum >> 64) as u64;
+    }
+
+    let mut result = [t[0], t[1], t[2], t[3]];
+    if t[4] != 0 || !lt(&result, &MODULUS) {
+        let mut borrow = false;
+        for (r, m) in result.iter_mut().zip(MODULUS) {
+            let (diff, b1) = r.overflowing_sub(m);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *r = diff;
+            borrow = b1 || b2;
+        }
+    }
+    result
+}
+
+/// Returns `true` if `a < b`.
+fn lt(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    a.iter().rev().cmp(b.iter().rev()).is_lt()
+}
+
+/// Converts a scalar out of Montgomery form, into 32 big-endian bytes.
+fn to_be_bytes(a: &[u64; 4]) -> [u8; 32] {
+    let a = mont_mul(a, &[1, 0, 0, 0]);
+    let mut bytes = [0u8; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(a.iter().rev()) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use hashbrown::HashMap;
+
+    #[test]
+    fn test_roots_of_unity() {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(root_of_unity(0), one);
+
+        // ω^2048 = -1
+        let minus_one = "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000";
+        assert_eq!(hex::encode(root_of_unity(1)), minus_one);
+
+        // ω^1024 is the primitive 4th root of unity.
+        let fourth_root = "00000000000000008d51ccce760304d0ec030002760300000001000000000000";
+        assert_eq!(hex::encode(root_of_unity(2)), fourth_root);
+
+        let last = "391b2856c609b4784ae25ffab9dc59865046d17864183203961a252dd8543362";
+        assert_eq!(hex::encode(root_of_unity(4095)), last);
+    }
+
+    /// A [PreimageOracleClient] backed by a map of rendered keys to preimages.
+    struct MapOracle(HashMap<[u8; 32], Vec<u8>>);
+
+    impl PreimageOracleClient for MapOracle {
+        fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+            self.0.get(&<[u8; 32]>::from(key)).cloned().ok_or_else(|| anyhow::anyhow!("missing"))
+        }
+
+        fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+            buf.copy_from_slice(&self.get(key)?);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_get_blob() {
+        let commitment = [0xAB; 48];
+        let field_elements = (0..FIELD_ELEMENTS_PER_BLOB).map(|i| {
+            let mut field_element = [0u8; 32];
+            field_element[24..].copy_from_slice(&(i as u64).to_be_bytes());
+            let key = PreimageKey::new_blob(&commitment, &root_of_unity(i));
+            (key.into(), field_element.to_vec())
+        });
+        let mut oracle = MapOracle(field_elements.collect());
+
+        let blob: Box<Blob> = get_blob(&mut oracle, &commitment).unwrap();
+        for (i, field_element) in blob.chunks_exact(32).enumerate() {
+            assert_eq!(field_element[24..], (i as u64).to_be_bytes());
+        }
+
+        assert!(get_blob(&mut oracle, &[0xCD; 48]).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Contains the [BootInfo] of the OP Stack fault proof program, and the local key identifiers that
+//! it is loaded from.
+//!
+//! The identifiers match the local keys that `op-program`'s host serves, so that a client built on
+//! [BootInfo] can be run against either host.
+
+use crate::{KeyValueStore, PreimageKey, PreimageKeyType, PreimageOracleClient};
+use alloc::vec::Vec;
+use anyhow::{anyhow, bail, Result};
+
+/// The local key identifier for the L1 head hash.
+pub const L1_HEAD_KEY: u64 = 1;
+
+/// The local key identifier for the agreed upon L2 output root.
+pub const L2_OUTPUT_ROOT_KEY: u64 = 2;
+
+/// The local key identifier for the disputed L2 output root claim.
+pub const L2_CLAIM_KEY: u64 = 3;
+
+/// The local key identifier for the L2 block number of the disputed claim.
+pub const L2_CLAIM_BLOCK_NUMBER_KEY: u64 = 4;
+
+/// The local key identifier for the L2 chain ID.
+pub const L2_CHAIN_ID_KEY: u64 = 5;
+
+/// The local key identifier for the JSON-encoded L2 chain config.
+pub const L2_CHAIN_CONFIG_KEY: u64 = 6;
+
+/// The local key identifier for the JSON-encoded rollup config.
+pub const L2_ROLLUP_CONFIG_KEY: u64 = 7;
+
+/// The boot information of the OP Stack fault proof program, loaded from the
+/// [PreimageKeyType::Local] keys served by the host.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BootInfo {
+    /// The L1 head hash that the program derives up to.
+    pub l1_head: [u8; 32],
+    /// The agreed upon L2 output root that the program starts from.
+    pub l2_output_root: [u8; 32],
+    /// The disputed L2 output root claim.
+    pub l2_claim: [u8; 32],
+    /// The L2 block number of the disputed claim.
+    pub l2_claim_block_number: u64,
+    /// The L2 chain ID.
+    pub l2_chain_id: u64,
+    /// The JSON-encoded L2 chain config.
+    pub l2_chain_config: Vec<u8>,
+    /// The JSON-encoded rollup config.
+    pub rollup_config: Vec<u8>,
+}
+
+impl BootInfo {
+    /// Loads and validates the [BootInfo] from the host through the given [PreimageOracleClient].
+    ///
+    /// # Returns
+    /// - `Ok(BootInfo)` if all local keys were fetched and are well-formed.
+    /// - `Err(_)` if a local key could not be fetched, or its preimage is malformed.
+    pub fn load<O: PreimageOracleClient + ?Sized>(oracle: &mut O) -> Result<Self> {
+        let mut l1_head = [0u8; 32];
+        oracle.get_exact(PreimageKey::new_local(L1_HEAD_KEY), &mut l1_head)?;
+
+        let mut l2_output_root = [0u8; 32];
+        oracle.get_exact(PreimageKey::new_local(L2_OUTPUT_ROOT_KEY), &mut l2_output_root)?;
+
+        let mut l2_claim = [0u8; 32];
+        oracle.get_exact(PreimageKey::new_local(L2_CLAIM_KEY), &mut l2_claim)?;
+
+        let l2_claim_block_number = load_u64(oracle, L2_CLAIM_BLOCK_NUMBER_KEY)?;
+        let l2_chain_id = load_u64(oracle, L2_CHAIN_ID_KEY)?;
+
+        let l2_chain_config = oracle.get(PreimageKey::new_local(L2_CHAIN_CONFIG_KEY))?;
+        if l2_chain_config.is_empty() {
+            bail!("Empty L2 chain config");
+        }
+
+        let rollup_config = oracle.get(PreimageKey::new_local(L2_ROLLUP_CONFIG_KEY))?;
+        if rollup_config.is_empty() {
+            bail!("Empty rollup config");
+        }
+
+        Ok(Self {
+            l1_head,
+            l2_output_root,
+            l2_claim,
+            l2_claim_block_number,
+            l2_chain_id,
+            l2_chain_config,
+            rollup_config,
+        })
+    }
+}
+
+/// The [BootInfo] is the provider of [PreimageKeyType::Local] keys on the host, and is read-only.
+impl KeyValueStore for BootInfo {
+    fn get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        if key.key_type() != PreimageKeyType::Local {
+            return Ok(None);
+        }
+
+        let key: [u8; 32] = key.into();
+        let ident = u64::from_be_bytes(key[24..].try_into().expect("Slice is 8 bytes"));
+        let value = match ident {
+            L1_HEAD_KEY => self.l1_head.to_vec(),
+            L2_OUTPUT_ROOT_KEY => self.l2_output_root.to_vec(),
+            L2_CLAIM_KEY => self.l2_claim.to_vec(),
+            L2_CLAIM_BLOCK_NUMBER_KEY => self.l2_claim_block_number.to_be_bytes().to_vec(),
+            L2_CHAIN_ID_KEY => self.l2_chain_id.to_be_bytes().to_vec(),
+            L2_CHAIN_CONFIG_KEY => self.l2_chain_config.clone(),
+            L2_ROLLUP_CONFIG_KEY => self.rollup_config.clone(),
+            _ => return Ok(None),
+        };
+        Ok(Some(value))
+    }
+
+    fn set(&mut self, key: PreimageKey, _: Vec<u8>) -> Result<()> {
+        bail!("Boot info is read-only, cannot set key: {}", key)
+    }
+}
+
+/// Loads a big-endian `u64` from the local key with the given identifier.
+fn load_u64<O: PreimageOracleClient + ?Sized>(oracle: &mut O, ident: u64) -> Result<u64> {
+    let value = oracle.get(PreimageKey::new_local(ident))?;
+    let bytes: [u8; 8] = value
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow!("Invalid length for local key {ident}: {}", value.len()))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BundleOracle, PreimageBundle};
+    use alloc::vec;
+
+    fn boot_info() -> BootInfo {
+        BootInfo {
+            l1_head: [1; 32],
+            l2_output_root: [2; 32],
+            l2_claim: [3; 32],
+            l2_claim_block_number: 4,
+            l2_chain_id: 10,
+            l2_chain_config: b"{\"chainId\":10}".to_vec(),
+            rollup_config: b"{\"l2_chain_id\":10}".to_vec(),
+        }
+    }
+
+    fn bundle_from(boot_info: &BootInfo) -> PreimageBundle {
+        let mut bundle = PreimageBundle::new();
+        for ident in L1_HEAD_KEY..=L2_ROLLUP_CONFIG_KEY {
+            let key = PreimageKey::new_local(ident);
+            bundle.insert_preimage(key, boot_info.get(key).unwrap().unwrap());
+        }
+        bundle
+    }
+
+    #[test]
+    fn test_boot_info_roundtrip() {
+        let expected = boot_info();
+        let mut oracle = BundleOracle::new(bundle_from(&expected));
+        assert_eq!(BootInfo::load(&mut oracle).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_boot_info_invalid_u64() {
+        let mut bundle = bundle_from(&boot_info());
+        bundle.insert_preimage(PreimageKey::new_local(L2_CHAIN_ID_KEY), vec![0; 4]);
+        let mut oracle = BundleOracle::new(bundle);
+        assert!(BootInfo::load(&mut oracle).is_err());
+    }
+
+    #[test]
+    fn test_boot_info_empty_config() {
+        let mut bundle = bundle_from(&boot_info());
+        bundle.insert_preimage(PreimageKey::new_local(L2_ROLLUP_CONFIG_KEY), vec![]);
+        let mut oracle = BundleOracle::new(bundle);
+        assert!(BootInfo::load(&mut oracle).is_err());
+    }
+
+    #[test]
+    fn test_boot_info_store() {
+        let mut boot_info = boot_info();
+        let global = PreimageKey::new([0xAA; 32], PreimageKeyType::Keccak256);
+        assert_eq!(boot_info.get(global).unwrap(), None);
+        assert_eq!(boot_info.get(PreimageKey::new_local(0)).unwrap(), None);
+        assert!(boot_info.set(PreimageKey::new_local(L1_HEAD_KEY), vec![]).is_err());
+    }
+}
@@ -0,0 +1,360 @@
+//! Contains the [PreimageBundle] type and its recording and replaying adapters.
+//!
+//! A [PreimageBundle] is a self-contained witness of a client program run: every
+//! `(PreimageKey, preimage)` pair that was requested from the host, along with every hint that was
+//! sent. Bundles are recorded against a live host with a [WitnessRecorder], serialized with
+//! [PreimageBundle::encode], and replayed offline with a [BundleOracle].
+
+use crate::{traits::stream_slice, HintWriterClient, PreimageKey, PreimageOracleClient};
+use alloc::{collections::BTreeMap, rc::Rc, string::String, vec::Vec};
+use anyhow::{anyhow, bail, ensure, Result};
+use core::cell::RefCell;
+
+/// The magic bytes that prefix an encoded [PreimageBundle].
+const BUNDLE_MAGIC: &[u8; 4] = b"kpb\x01";
+
+/// A [PreimageBundle] is a recording of the preimages and hints exchanged between a client program
+/// and the host.
+///
+/// **Encoding** (all integers are big-endian):
+/// | Field          | Description                                               |
+/// |----------------|-----------------------------------------------------------|
+/// | `magic`        | `b"kpb\x01"`                                              |
+/// | `u64`          | Number of preimages                                       |
+/// | `preimages...` | 32-byte key, `u64` length prefix, preimage data           |
+/// | `u64`          | Number of hints                                           |
+/// | `hints...`     | `u32` length prefix, UTF-8 hint string                    |
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PreimageBundle {
+    /// The recorded preimages, keyed by the rendered [PreimageKey].
+    preimages: BTreeMap<[u8; 32], Vec<u8>>,
+    /// The recorded hints, in the order they were sent.
+    hints: Vec<String>,
+}
+
+impl PreimageBundle {
+    /// Create a new, empty [PreimageBundle].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a preimage for the given [PreimageKey].
+    pub fn insert_preimage(&mut self, key: PreimageKey, preimage: Vec<u8>) {
+        self.preimages.insert(key.into(), preimage);
+    }
+
+    /// Records a hint.
+    pub fn push_hint(&mut self, hint: &str) {
+        self.hints.push(String::from(hint));
+    }
+
+    /// Returns the recorded preimage for the given [PreimageKey], if it exists.
+    pub fn preimage(&self, key: PreimageKey) -> Option<&[u8]> {
+        self.preimages.get(&<[u8; 32]>::from(key)).map(Vec::as_slice)
+    }
+
+    /// Returns an iterator over the recorded preimages, ordered by their rendered keys.
+    pub fn preimages(&self) -> impl Iterator<Item = (&[u8; 32], &[u8])> {
+        self.preimages.iter().map(|(key, preimage)| (key, preimage.as_slice()))
+    }
+
+    /// Returns the recorded hints, in the order they were sent.
+    pub fn hints(&self) -> &[String] {
+        &self.hints
+    }
+
+    /// Encodes the [PreimageBundle] into its binary format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(BUNDLE_MAGIC);
+
+        buf.extend_from_slice(&(self.preimages.len() as u64).to_be_bytes());
+        for (key, preimage) in self.preimages.iter() {
+            buf.extend_from_slice(key);
+            buf.extend_from_slice(&(preimage.len() as u64).to_be_bytes());
+            buf.extend_from_slice(preimage);
+        }
+
+        buf.extend_from_slice(&(self.hints.len() as u64).to_be_bytes());
+        for hint in self.hints.iter() {
+            buf.extend_from_slice(&(hint.len() as u32).to_be_bytes());
+            buf.extend_from_slice(hint.as_bytes());
+        }
+
+        buf
+    }
+
+    /// Decodes a [PreimageBundle] from its binary format.
+    pub fn decode(mut buf: &[u8]) -> Result<Self> {
+        ensure!(take(&mut buf, 4)? == BUNDLE_MAGIC, "Invalid preimage bundle magic");
+
+        let mut bundle = Self::new();
+        for _ in 0..take_u64(&mut buf)? {
+            let key: [u8; 32] = take(&mut buf, 32)?.try_into()?;
+            let len = take_u64(&mut buf)? as usize;
+            bundle.preimages.insert(key, take(&mut buf, len)?.to_vec());
+        }
+        for _ in 0..take_u64(&mut buf)? {
+            let len = u32::from_be_bytes(take(&mut buf, 4)?.try_into()?) as usize;
+            let hint = String::from_utf8(take(&mut buf, len)?.to_vec())
+                .map_err(|e| anyhow!("Invalid hint in preimage bundle: {e}"))?;
+            bundle.hints.push(hint);
+        }
+
+        ensure!(buf.is_empty(), "Trailing bytes after preimage bundle");
+        Ok(bundle)
+    }
+}
+
+/// Splits `len` bytes off the front of `buf`.
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        bail!("Unexpected end of preimage bundle");
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+/// Splits a big-endian `u64` off the front of `buf`.
+fn take_u64(buf: &mut &[u8]) -> Result<u64> {
+    Ok(u64::from_be_bytes(take(buf, 8)?.try_into()?))
+}
+
+/// A [WitnessRecorder] records the preimages and hints exchanged with the host into a shared
+/// [PreimageBundle]. The [RecordingOracle] and [RecordingHintWriter] adapters it creates all write
+/// into the same bundle.
+#[derive(Debug, Default, Clone)]
+pub struct WitnessRecorder {
+    bundle: Rc<RefCell<PreimageBundle>>,
+}
+
+impl WitnessRecorder {
+    /// Create a new [WitnessRecorder] with an empty [PreimageBundle].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps a [PreimageOracleClient] so that every preimage it returns is recorded.
+    pub fn oracle<O: PreimageOracleClient>(&self, inner: O) -> RecordingOracle<O> {
+        RecordingOracle { inner, bundle: self.bundle.clone() }
+    }
+
+    /// Wraps a [HintWriterClient] so that every hint it sends is recorded.
+    pub fn hint_writer<H: HintWriterClient>(&self, inner: H) -> RecordingHintWriter<H> {
+        RecordingHintWriter { inner, bundle: self.bundle.clone() }
+    }
+
+    /// Returns a snapshot of the [PreimageBundle] recorded so far.
+    pub fn bundle(&self) -> PreimageBundle {
+        self.bundle.borrow().clone()
+    }
+}
+
+/// A [RecordingOracle] is a [PreimageOracleClient] that records every preimage returned by the
+/// inner oracle. It is created by [WitnessRecorder::oracle].
+#[derive(Debug, Clone)]
+pub struct RecordingOracle<O: PreimageOracleClient> {
+    inner: O,
+    bundle: Rc<RefCell<PreimageBundle>>,
+}
+
+impl<O: PreimageOracleClient> PreimageOracleClient for RecordingOracle<O> {
+    fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+        let data = self.inner.get(key)?;
+        self.bundle.borrow_mut().insert_preimage(key, data.clone());
+        Ok(data)
+    }
+
+    fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        self.inner.get_exact(key, buf)?;
+        self.bundle.borrow_mut().insert_preimage(key, buf.to_vec());
+        Ok(())
+    }
+
+    fn stream_into(
+        &mut self,
+        key: PreimageKey,
+        buf: &mut [u8],
+        sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let mut data = Vec::new();
+        self.inner.stream_into(key, buf, &mut |chunk| {
+            data.extend_from_slice(chunk);
+            sink(chunk)
+        })?;
+        self.bundle.borrow_mut().insert_preimage(key, data);
+        Ok(())
+    }
+}
+
+/// A [RecordingHintWriter] is a [HintWriterClient] that records every hint sent through the inner
+/// hint writer. It is created by [WitnessRecorder::hint_writer].
+#[derive(Debug, Clone)]
+pub struct RecordingHintWriter<H: HintWriterClient> {
+    inner: H,
+    bundle: Rc<RefCell<PreimageBundle>>,
+}
+
+impl<H: HintWriterClient> HintWriterClient for RecordingHintWriter<H> {
+    fn write(&self, hint: &str) -> Result<()> {
+        self.inner.write(hint)?;
+        self.bundle.borrow_mut().push_hint(hint);
+        Ok(())
+    }
+}
+
+/// A [BundleOracle] replays a recorded [PreimageBundle] offline. It serves recorded preimages, and
+/// fails on any [PreimageKey] that was not recorded. Hints are accepted and ignored, as every
+/// preimage is already available.
+#[derive(Debug, Clone)]
+pub struct BundleOracle {
+    bundle: PreimageBundle,
+}
+
+impl BundleOracle {
+    /// Create a new [BundleOracle] from a [PreimageBundle].
+    pub fn new(bundle: PreimageBundle) -> Self {
+        Self { bundle }
+    }
+
+    /// Returns a reference to the replayed [PreimageBundle].
+    pub fn bundle(&self) -> &PreimageBundle {
+        &self.bundle
+    }
+}
+
+impl PreimageOracleClient for BundleOracle {
+    fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+        self.bundle.preimage(key).map(<[u8]>::to_vec).ok_or_else(|| missing_preimage(key))
+    }
+
+    fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let data = self.bundle.preimage(key).ok_or_else(|| missing_preimage(key))?;
+        if buf.len() != data.len() {
+            bail!("Buffer size {} does not match preimage size {}", buf.len(), data.len());
+        }
+        buf.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn stream_into(
+        &mut self,
+        key: PreimageKey,
+        buf: &mut [u8],
+        sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let data = self.bundle.preimage(key).ok_or_else(|| missing_preimage(key))?;
+        stream_slice(data, buf, sink)
+    }
+}
+
+impl HintWriterClient for BundleOracle {
+    fn write(&self, _: &str) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the error for a [PreimageKey] that is missing from the replayed [PreimageBundle].
+fn missing_preimage(key: PreimageKey) -> anyhow::Error {
+    anyhow!("Preimage not recorded in bundle: 0x{}", hex::encode(<[u8; 32]>::from(key)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PreimageKeyType;
+    use alloc::vec;
+
+    /// A [PreimageOracleClient] that returns the rendered key as the preimage.
+    struct EchoOracle;
+
+    impl PreimageOracleClient for EchoOracle {
+        fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+            Ok(<[u8; 32]>::from(key).to_vec())
+        }
+
+        fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+            buf.copy_from_slice(&<[u8; 32]>::from(key));
+            Ok(())
+        }
+    }
+
+    /// A [HintWriterClient] that accepts every hint.
+    struct NoopHintWriter;
+
+    impl HintWriterClient for NoopHintWriter {
+        fn write(&self, _: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let recorder = WitnessRecorder::new();
+        let mut oracle = recorder.oracle(EchoOracle);
+        let hint_writer = recorder.hint_writer(NoopHintWriter);
+
+        let keccak_key = PreimageKey::new([0xAA; 32], PreimageKeyType::Keccak256);
+        let local_key = PreimageKey::new_local(7);
+        hint_writer.write("l1-block-header 0xaaaa").unwrap();
+        let keccak_preimage = oracle.get(keccak_key).unwrap();
+        let mut local_preimage = [0u8; 32];
+        oracle.get_exact(local_key, &mut local_preimage).unwrap();
+
+        let encoded = recorder.bundle().encode();
+        let bundle = PreimageBundle::decode(&encoded).unwrap();
+        assert_eq!(bundle, recorder.bundle());
+        assert_eq!(bundle.hints(), ["l1-block-header 0xaaaa"]);
+        assert_eq!(bundle.preimages().count(), 2);
+
+        let mut replay = BundleOracle::new(bundle);
+        replay.write("l1-block-header 0xaaaa").unwrap();
+        assert_eq!(replay.get(keccak_key).unwrap(), keccak_preimage);
+        let mut buf = [0u8; 32];
+        replay.get_exact(local_key, &mut buf).unwrap();
+        assert_eq!(buf, local_preimage);
+
+        let unknown_key = PreimageKey::new([0xBB; 32], PreimageKeyType::Keccak256);
+        assert!(replay.get(unknown_key).is_err());
+        assert!(replay.get_exact(local_key, &mut [0u8; 31]).is_err());
+    }
+
+    #[test]
+    fn test_record_and_replay_stream() {
+        let recorder = WitnessRecorder::new();
+        let mut oracle = recorder.oracle(EchoOracle);
+        let key = PreimageKey::new([0xCC; 32], PreimageKeyType::Keccak256);
+
+        let mut recorded = Vec::new();
+        oracle
+            .stream_into(key, &mut [0u8; 5], &mut |chunk| {
+                recorded.extend_from_slice(chunk);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(recorder.bundle().preimage(key), Some(recorded.as_slice()));
+
+        let mut replay = BundleOracle::new(recorder.bundle());
+        let mut replayed = Vec::new();
+        replay
+            .stream_into(key, &mut [0u8; 7], &mut |chunk| {
+                replayed.extend_from_slice(chunk);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(replayed, recorded);
+    }
+
+    #[test]
+    fn test_decode_invalid_bundle() {
+        let mut bundle = PreimageBundle::new();
+        bundle.insert_preimage(PreimageKey::new_local(0), vec![1, 2, 3]);
+        bundle.push_hint("l2-output 0x00");
+        let encoded = bundle.encode();
+
+        assert!(PreimageBundle::decode(&encoded[..encoded.len() - 1]).is_err());
+        assert!(PreimageBundle::decode(&[encoded.as_slice(), &[0]].concat()).is_err());
+        assert!(PreimageBundle::decode(&encoded[1..]).is_err());
+    }
+}
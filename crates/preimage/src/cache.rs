@@ -0,0 +1,300 @@
+//! Contains the [CachingOracle], a memoizing [PreimageOracleClient] wrapper with LRU eviction.
+
+use crate::{traits::stream_slice, PreimageKey, PreimageOracleClient};
+use alloc::{collections::BTreeMap, vec::Vec};
+use anyhow::{bail, Result};
+use hashbrown::HashMap;
+
+/// A cached preimage, along with the tick at which it was last accessed.
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    data: Vec<u8>,
+    last_used: u64,
+}
+
+/// A [CachingOracle] wraps a [PreimageOracleClient] and memoizes the preimages it returns, so that
+/// repeated requests for the same [PreimageKey] do not incur a round-trip to the host.
+///
+/// The cache may optionally be bounded by the total size of the cached preimages in bytes. When
+/// inserting a preimage would exceed the bound, the least recently used preimages are evicted
+/// until it fits. Preimages that are larger than the bound on their own are never cached.
+#[derive(Debug, Clone)]
+pub struct CachingOracle<O: PreimageOracleClient> {
+    /// The inner oracle that is queried on cache misses.
+    inner: O,
+    /// The cached preimages, keyed by the rendered [PreimageKey].
+    entries: HashMap<[u8; 32], CacheEntry>,
+    /// The keys of the cached preimages, ordered from least to most recently used.
+    lru: BTreeMap<u64, [u8; 32]>,
+    /// A monotonically increasing counter used to order accesses.
+    tick: u64,
+    /// The total size of the cached preimages, in bytes.
+    size: usize,
+    /// The optional maximum total size of the cached preimages, in bytes.
+    max_size: Option<usize>,
+    /// The number of requests served from the cache.
+    hits: u64,
+    /// The number of requests forwarded to the inner oracle.
+    misses: u64,
+}
+
+impl<O: PreimageOracleClient> CachingOracle<O> {
+    /// Create a new [CachingOracle] that wraps the given [PreimageOracleClient]. If `max_size` is
+    /// `Some`, the total size of the cached preimages is bounded to `max_size` bytes.
+    pub fn new(inner: O, max_size: Option<usize>) -> Self {
+        Self {
+            inner,
+            entries: HashMap::new(),
+            lru: BTreeMap::new(),
+            tick: 0,
+            size: 0,
+            max_size,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the number of requests that were served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of requests that were forwarded to the inner oracle.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Returns the total size of the cached preimages, in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of cached preimages.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no preimages are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Evicts all cached preimages. The hit and miss counters are left untouched.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.lru.clear();
+        self.size = 0;
+    }
+
+    /// Consumes the [CachingOracle], returning the inner [PreimageOracleClient].
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+
+    /// Looks up a cached preimage, marking it as the most recently used on a hit.
+    fn lookup(&mut self, key: &[u8; 32]) -> Option<&[u8]> {
+        let tick = self.next_tick();
+        let Some(entry) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+
+        self.lru.remove(&entry.last_used);
+        self.lru.insert(tick, *key);
+        entry.last_used = tick;
+        Some(&entry.data)
+    }
+
+    /// Inserts a preimage into the cache, evicting the least recently used preimages if the size
+    /// bound would be exceeded.
+    fn insert(&mut self, key: [u8; 32], data: Vec<u8>) {
+        if self.max_size.is_some_and(|max_size| data.len() > max_size) {
+            return;
+        }
+
+        if let Some(max_size) = self.max_size {
+            while self.size + data.len() > max_size {
+                let Some((_, evicted)) = self.lru.pop_first() else { break };
+                if let Some(entry) = self.entries.remove(&evicted) {
+                    self.size -= entry.data.len();
+                }
+            }
+        }
+
+        let tick = self.next_tick();
+        self.size += data.len();
+        self.lru.insert(tick, key);
+        self.entries.insert(key, CacheEntry { data, last_used: tick });
+    }
+
+    /// Returns the next access tick.
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+impl<O: PreimageOracleClient> PreimageOracleClient for CachingOracle<O> {
+    fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+        let rendered_key: [u8; 32] = key.into();
+        if let Some(data) = self.lookup(&rendered_key) {
+            return Ok(data.to_vec());
+        }
+
+        let data = self.inner.get(key)?;
+        self.insert(rendered_key, data.clone());
+        Ok(data)
+    }
+
+    fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let rendered_key: [u8; 32] = key.into();
+        if let Some(data) = self.lookup(&rendered_key) {
+            if buf.len() != data.len() {
+                bail!("Buffer size {} does not match preimage size {}", buf.len(), data.len());
+            }
+            buf.copy_from_slice(data);
+            return Ok(());
+        }
+
+        self.inner.get_exact(key, buf)?;
+        self.insert(rendered_key, buf.to_vec());
+        Ok(())
+    }
+
+    fn stream_into(
+        &mut self,
+        key: PreimageKey,
+        buf: &mut [u8],
+        sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let rendered_key: [u8; 32] = key.into();
+        if let Some(data) = self.lookup(&rendered_key) {
+            return stream_slice(data, buf, sink);
+        }
+
+        // Collect the preimage as it is streamed, unless it grows too large to be cached.
+        let max_size = self.max_size;
+        let mut data = Some(Vec::new());
+        self.inner.stream_into(key, buf, &mut |chunk| {
+            if let Some(collected) = data.as_mut() {
+                if max_size.is_some_and(|max_size| collected.len() + chunk.len() > max_size) {
+                    data = None;
+                } else {
+                    collected.extend_from_slice(chunk);
+                }
+            }
+            sink(chunk)
+        })?;
+
+        if let Some(data) = data {
+            self.insert(rendered_key, data);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PreimageKeyType;
+    use alloc::vec;
+
+    /// A [PreimageOracleClient] that returns `n` copies of `n`, where `n` is the last byte of the
+    /// key, and counts the number of requests it has served.
+    #[derive(Default)]
+    struct CountingOracle {
+        requests: usize,
+    }
+
+    impl PreimageOracleClient for CountingOracle {
+        fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+            self.requests += 1;
+            let key: [u8; 32] = key.into();
+            Ok(vec![key[31]; key[31] as usize])
+        }
+
+        fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+            let data = self.get(key)?;
+            buf.copy_from_slice(&data);
+            Ok(())
+        }
+    }
+
+    fn key(n: u8) -> PreimageKey {
+        let mut key = [0u8; 32];
+        key[31] = n;
+        PreimageKey::new(key, PreimageKeyType::Keccak256)
+    }
+
+    #[test]
+    fn test_caching_oracle_memoizes() {
+        let mut oracle = CachingOracle::new(CountingOracle::default(), None);
+
+        assert_eq!(oracle.get(key(4)).unwrap(), [4; 4]);
+        assert_eq!(oracle.get(key(4)).unwrap(), [4; 4]);
+        let mut buf = [0u8; 4];
+        oracle.get_exact(key(4), &mut buf).unwrap();
+        assert_eq!(buf, [4; 4]);
+        assert!(oracle.get_exact(key(4), &mut [0u8; 3]).is_err());
+
+        assert_eq!(oracle.hits(), 3);
+        assert_eq!(oracle.misses(), 1);
+        assert_eq!(oracle.size(), 4);
+        assert_eq!(oracle.into_inner().requests, 1);
+    }
+
+    #[test]
+    fn test_caching_oracle_lru_eviction() {
+        let mut oracle = CachingOracle::new(CountingOracle::default(), Some(10));
+
+        oracle.get(key(4)).unwrap();
+        oracle.get(key(5)).unwrap();
+        // Touch key 4 so that key 5 is the least recently used.
+        oracle.get(key(4)).unwrap();
+        // Inserting key 3 exceeds the bound, and evicts key 5.
+        oracle.get(key(3)).unwrap();
+        assert_eq!(oracle.len(), 2);
+        assert_eq!(oracle.size(), 7);
+
+        oracle.get(key(4)).unwrap();
+        oracle.get(key(3)).unwrap();
+        oracle.get(key(5)).unwrap();
+        assert_eq!(oracle.hits(), 3);
+        assert_eq!(oracle.misses(), 4);
+    }
+
+    #[test]
+    fn test_caching_oracle_skips_oversized() {
+        let mut oracle = CachingOracle::new(CountingOracle::default(), Some(10));
+
+        oracle.get(key(11)).unwrap();
+        oracle.get(key(11)).unwrap();
+        assert!(oracle.is_empty());
+        assert_eq!(oracle.misses(), 2);
+        assert_eq!(oracle.into_inner().requests, 2);
+    }
+
+    #[test]
+    fn test_caching_oracle_stream() {
+        let mut oracle = CachingOracle::new(CountingOracle::default(), Some(10));
+
+        for _ in 0..2 {
+            let mut streamed = Vec::new();
+            oracle
+                .stream_into(key(5), &mut [0u8; 2], &mut |chunk| {
+                    streamed.extend_from_slice(chunk);
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(streamed, [5; 5]);
+        }
+        assert_eq!(oracle.hits(), 1);
+        assert_eq!(oracle.misses(), 1);
+
+        // Streamed preimages that exceed the bound are not cached.
+        oracle.stream_into(key(11), &mut [0u8; 4], &mut |_| Ok(())).unwrap();
+        assert_eq!(oracle.len(), 1);
+        assert_eq!(oracle.into_inner().requests, 2);
+    }
+}
@@ -0,0 +1,234 @@
+//! This module contains the [DuplexChannel], an in-memory [Channel] for running the client and host
+//! halves of the preimage protocols in the same process.
+
+use crate::traits::Channel;
+use alloc::{collections::VecDeque, sync::Arc};
+use anyhow::{anyhow, Result};
+use cfg_if::cfg_if;
+use core::fmt;
+
+/// A [DuplexChannel] is one end of an in-memory, bidirectional byte stream. Reads block until the
+/// other end has written enough bytes, or has been dropped. With the `std` feature, blocked readers
+/// park on a condition variable; otherwise they spin.
+///
+/// Ends are created in pairs with [DuplexChannel::pair]. Cloning an end shares its underlying
+/// buffers, and the end is only closed once its last handle is dropped.
+#[derive(Debug, Clone)]
+pub struct DuplexChannel {
+    end: Arc<End>,
+}
+
+impl DuplexChannel {
+    /// Create a new pair of connected [DuplexChannel] ends. Bytes written to one end are read from
+    /// the other.
+    pub fn pair() -> (Self, Self) {
+        let (a, b) = (Arc::new(Pipe::default()), Arc::new(Pipe::default()));
+        (
+            Self { end: Arc::new(End { inbound: a.clone(), outbound: b.clone() }) },
+            Self { end: Arc::new(End { inbound: b, outbound: a }) },
+        )
+    }
+}
+
+impl Channel for DuplexChannel {
+    fn read_exact(&self, buf: &mut [u8]) -> Result<()> {
+        self.end.inbound.read_exact(buf)
+    }
+
+    fn write_all(&self, buf: &[u8]) -> Result<()> {
+        self.end.outbound.write_all(buf);
+        Ok(())
+    }
+}
+
+/// One end of a [DuplexChannel], shared by all of its handles. Dropping it closes the pipe that
+/// the other end reads from.
+#[derive(Debug)]
+struct End {
+    /// The pipe of bytes written by the other end.
+    inbound: Arc<Pipe>,
+    /// The pipe of bytes written by this end.
+    outbound: Arc<Pipe>,
+}
+
+impl Drop for End {
+    fn drop(&mut self) {
+        self.outbound.close();
+    }
+}
+
+/// The state of a one-directional pipe between the two ends of a [DuplexChannel].
+#[derive(Debug, Default)]
+struct PipeState {
+    /// The bytes written to the pipe that have not been read yet.
+    buf: VecDeque<u8>,
+    /// Whether the writing end of the pipe has been dropped.
+    closed: bool,
+}
+
+impl PipeState {
+    /// Moves as many buffered bytes as fit into `buf`, returning the number of bytes moved.
+    fn drain_into(&mut self, buf: &mut [u8]) -> usize {
+        let n = self.buf.len().min(buf.len());
+        for (dst, src) in buf.iter_mut().zip(self.buf.drain(..n)) {
+            *dst = src;
+        }
+        n
+    }
+}
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+        /// A one-directional pipe, whose readers block on a [Condvar] until bytes are written or
+        /// the pipe is closed.
+        #[derive(Debug, Default)]
+        struct Pipe {
+            state: Mutex<PipeState>,
+            ready: Condvar,
+        }
+
+        impl Pipe {
+            fn lock(&self) -> MutexGuard<'_, PipeState> {
+                self.state.lock().unwrap_or_else(PoisonError::into_inner)
+            }
+
+            fn read_exact(&self, buf: &mut [u8]) -> Result<()> {
+                let mut state = self.lock();
+                let mut read = 0;
+                loop {
+                    read += state.drain_into(&mut buf[read..]);
+                    if read == buf.len() {
+                        return Ok(());
+                    }
+                    if state.closed {
+                        return Err(anyhow!(ChannelClosed { read, len: buf.len() }));
+                    }
+                    state = self.ready.wait(state).unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+
+            fn write_all(&self, buf: &[u8]) {
+                self.lock().buf.extend(buf);
+                self.ready.notify_all();
+            }
+
+            fn close(&self) {
+                self.lock().closed = true;
+                self.ready.notify_all();
+            }
+        }
+    } else {
+        use spin::Mutex;
+
+        /// A one-directional pipe, whose readers spin until bytes are written or the pipe is
+        /// closed.
+        #[derive(Debug, Default)]
+        struct Pipe {
+            state: Mutex<PipeState>,
+        }
+
+        impl Pipe {
+            fn read_exact(&self, buf: &mut [u8]) -> Result<()> {
+                let mut read = 0;
+                loop {
+                    let mut state = self.state.lock();
+                    read += state.drain_into(&mut buf[read..]);
+                    if read == buf.len() {
+                        return Ok(());
+                    }
+                    if state.closed {
+                        return Err(anyhow!(ChannelClosed { read, len: buf.len() }));
+                    }
+                    drop(state);
+                    core::hint::spin_loop();
+                }
+            }
+
+            fn write_all(&self, buf: &[u8]) {
+                self.state.lock().buf.extend(buf);
+            }
+
+            fn close(&self) {
+                self.state.lock().closed = true;
+            }
+        }
+    }
+}
+
+/// The error returned by [DuplexChannel] reads once the other end has been dropped and all bytes it
+/// wrote have been read, and by [PipeHandle](crate::PipeHandle) reads that reach EOF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelClosed {
+    /// The number of bytes that were read before the channel closed.
+    pub read: usize,
+    /// The number of bytes that were requested.
+    pub len: usize,
+}
+
+impl fmt::Display for ChannelClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Channel closed after reading {} of {} bytes", self.read, self.len)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChannelClosed {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_duplex_channel() {
+        let (a, b) = DuplexChannel::pair();
+        a.write_all(b"hello").unwrap();
+        b.write_all(b"world").unwrap();
+
+        let mut buf = [0u8; 5];
+        b.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello");
+        a.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"world");
+
+        // Reading from a closed channel fails once the buffered data is exhausted.
+        a.write_all(b"!").unwrap();
+        drop(a);
+        let mut buf = [0u8; 2];
+        let err = b.read_exact(&mut buf).unwrap_err();
+        assert_eq!(err.downcast_ref::<ChannelClosed>(), Some(&ChannelClosed { read: 1, len: 2 }));
+    }
+
+    #[test]
+    fn test_duplex_channel_closes_after_last_handle() {
+        let (a, b) = DuplexChannel::pair();
+        let a_clone = a.clone();
+        drop(a);
+
+        // The end stays open while a clone of it is alive.
+        a_clone.write_all(b"hi").unwrap();
+        let mut buf = [0u8; 2];
+        b.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hi");
+
+        drop(a_clone);
+        let err = b.read_exact(&mut buf).unwrap_err();
+        assert_eq!(err.downcast_ref::<ChannelClosed>(), Some(&ChannelClosed { read: 0, len: 2 }));
+    }
+
+    #[test]
+    fn test_duplex_channel_blocking_read() {
+        extern crate std;
+
+        let (a, b) = DuplexChannel::pair();
+        let reader = std::thread::spawn(move || {
+            let mut buf = [0u8; 4];
+            b.read_exact(&mut buf).map(|_| buf)
+        });
+
+        a.write_all(b"ab").unwrap();
+        a.write_all(b"cd").unwrap();
+        assert_eq!(&reader.join().unwrap().unwrap(), b"abcd");
+    }
+}
@@ -1,22 +1,26 @@
-use crate::{traits::HintWriterClient, PipeHandle};
-use alloc::vec;
-use anyhow::Result;
+use crate::{
+    traits::{HintReaderServer, HintRouter, HintWriterClient},
+    Channel, ChannelClosed, Hint, HintType, PipeHandle,
+};
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec};
+use anyhow::{anyhow, Result};
+use core::fmt::Debug;
 
 /// A [HintWriter] is a high-level interface to the hint pipe. It provides a way to write hints to
 /// the host.
 #[derive(Debug, Clone, Copy)]
-pub struct HintWriter {
-    pipe_handle: PipeHandle,
+pub struct HintWriter<C: Channel = PipeHandle> {
+    channel: C,
 }
 
-impl HintWriter {
-    /// Create a new [HintWriter] from a [PipeHandle].
-    pub fn new(pipe_handle: PipeHandle) -> Self {
-        Self { pipe_handle }
+impl<C: Channel> HintWriter<C> {
+    /// Create a new [HintWriter] from a [Channel], such as a [PipeHandle].
+    pub fn new(channel: C) -> Self {
+        Self { channel }
     }
 }
 
-impl HintWriterClient for HintWriter {
+impl<C: Channel> HintWriterClient for HintWriter<C> {
     /// Write a hint to the host. This will overwrite any existing hint in the pipe, and block until
     /// all data has been written.
     fn write(&self, hint: &str) -> Result<()> {
@@ -27,12 +31,201 @@ impl HintWriterClient for HintWriter {
         hint_bytes[4..].copy_from_slice(hint.as_bytes());
 
         // Write the hint to the host.
-        self.pipe_handle.write(&hint_bytes)?;
+        self.channel.write_all(&hint_bytes)?;
 
         // Read the hint acknowledgement from the host.
         let mut hint_ack = [0u8; 1];
-        self.pipe_handle.read_exact(&mut hint_ack)?;
+        self.channel.read_exact(&mut hint_ack)?;
 
         Ok(())
     }
 }
+
+/// A [HintReader] is a router for hints sent by the [HintWriter] from the client program. It
+/// provides a way for the host to prepare preimages for reading.
+#[derive(Debug, Clone, Copy)]
+pub struct HintReader<C: Channel = PipeHandle> {
+    channel: C,
+}
+
+impl<C: Channel> HintReader<C> {
+    /// Create a new [HintReader] from a [Channel], such as a [PipeHandle].
+    pub fn new(channel: C) -> Self {
+        Self { channel }
+    }
+}
+
+impl<C: Channel> HintReaderServer for HintReader<C> {
+    /// Read the next hint from the client, route it, and acknowledge it. The acknowledgement is
+    /// always sent once routing has finished so that the client is never left blocked, even if the
+    /// hint could not be handled.
+    fn next_hint<R>(&self, router: &mut R) -> Result<()>
+    where
+        R: HintRouter,
+    {
+        // Read the length of the raw hint payload.
+        let mut len_buf = [0u8; 4];
+        self.channel.read_exact(&mut len_buf)?;
+        let len = u32::from_be_bytes(len_buf);
+
+        // Read the raw hint payload. A channel that closes here has already delivered the length
+        // prefix, so the bytes read are counted from the start of the hint.
+        let mut raw_payload = vec![0u8; len as usize];
+        self.channel.read_exact(raw_payload.as_mut_slice()).map_err(|e| {
+            match e.downcast::<ChannelClosed>() {
+                Ok(closed) => anyhow!(ChannelClosed {
+                    read: len_buf.len() + closed.read,
+                    len: len_buf.len() + closed.len,
+                }),
+                Err(e) => e,
+            }
+        })?;
+        let payload = String::from_utf8(raw_payload)
+            .map_err(|e| anyhow!("Failed to decode hint payload: {e}"))?;
+
+        // Route the hint, and acknowledge it once the handler has finished.
+        let result = router.route_hint(&payload);
+        self.channel.write_all(&[0x00])?;
+
+        result
+    }
+}
+
+/// A boxed hint handler, which receives the data portion of a hint (everything after the hint
+/// type).
+type HintHandler<'a> = Box<dyn FnMut(&str) -> Result<()> + 'a>;
+
+/// A [HintRegistry] is a [HintRouter] that dispatches hints to handlers keyed by the hint type,
+/// which is the first word of the hint string (e.g. `sha2-preimage` in `sha2-preimage 0xabcd`).
+/// Handlers for the [HintType]s of the fault proof program can be registered with
+/// [HintRegistry::register_type], which decodes the hint data as a [Hint] does.
+#[derive(Default)]
+pub struct HintRegistry<'a> {
+    handlers: BTreeMap<String, HintHandler<'a>>,
+}
+
+impl<'a> HintRegistry<'a> {
+    /// Create a new, empty [HintRegistry].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a handler for the given hint type. Any handler previously registered for the same
+    /// hint type is replaced.
+    pub fn register<F>(&mut self, hint_type: &str, handler: F)
+    where
+        F: FnMut(&str) -> Result<()> + 'a,
+    {
+        self.handlers.insert(String::from(hint_type), Box::new(handler));
+    }
+
+    /// Register a handler for the given [HintType], which receives the decoded data of the
+    /// [Hint]. Any handler previously registered for the same hint type is replaced.
+    pub fn register_type<F>(&mut self, hint_type: HintType, mut handler: F)
+    where
+        F: FnMut(&[u8]) -> Result<()> + 'a,
+    {
+        self.register(hint_type.as_str(), move |data| handler(&Hint::parse_data(data)?));
+    }
+}
+
+impl HintRouter for HintRegistry<'_> {
+    fn route_hint(&mut self, hint: &str) -> Result<()> {
+        let (hint_type, hint_data) = hint.split_once(' ').unwrap_or((hint, ""));
+        let handler = self
+            .handlers
+            .get_mut(hint_type)
+            .ok_or_else(|| anyhow!("No handler registered for hint type: {hint_type}"))?;
+        handler(hint_data)
+    }
+}
+
+impl Debug for HintRegistry<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HintRegistry").field("hint_types", &self.handlers.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use super::*;
+    use crate::DuplexChannel;
+    use alloc::{string::ToString, vec::Vec};
+    use std::sync::Mutex;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_hint_client_and_host() {
+        let (client_channel, host_channel) = DuplexChannel::pair();
+        let hint_writer = HintWriter::new(client_channel);
+        let hint_reader = HintReader::new(host_channel);
+
+        let client = tokio::task::spawn(async move {
+            hint_writer.write_hint(&Hint::new(HintType::L1BlockHeader, vec![0xfa, 0xca, 0xde]))
+        });
+        let host = tokio::task::spawn(async move {
+            let received = Mutex::new(Vec::new());
+            let mut registry = HintRegistry::new();
+            registry.register_type(HintType::L1BlockHeader, |data| {
+                received.lock().unwrap().push(data.to_vec());
+                Ok(())
+            });
+            hint_reader.next_hint(&mut registry).unwrap();
+            drop(registry);
+            received.into_inner().unwrap()
+        });
+
+        let (c, h) = tokio::join!(client, host);
+        c.unwrap().unwrap();
+        assert_eq!(h.unwrap(), [[0xfa, 0xca, 0xde]]);
+    }
+
+    #[test]
+    fn test_hint_reader_partial_frame() {
+        let (client_channel, host_channel) = DuplexChannel::pair();
+        let hint_reader = HintReader::new(host_channel);
+
+        // A hint cut off after its length prefix is reported as a partial read of the hint.
+        client_channel.write_all(&8u32.to_be_bytes()).unwrap();
+        drop(client_channel);
+        let err = hint_reader.next_hint(&mut HintRegistry::new()).unwrap_err();
+        assert_eq!(err.downcast_ref::<ChannelClosed>(), Some(&ChannelClosed { read: 4, len: 12 }));
+    }
+
+    #[test]
+    fn test_hint_registry_routing() {
+        let mut routed = Vec::new();
+        let mut registry = HintRegistry::new();
+        registry.register("sha2-preimage", |data| {
+            routed.push(data.to_string());
+            Ok(())
+        });
+
+        registry.route_hint("sha2-preimage 0xbeef").unwrap();
+        registry.route_hint("sha2-preimage").unwrap();
+        assert!(registry.route_hint("l2-block-header 0xbeef").is_err());
+        drop(registry);
+
+        assert_eq!(routed, ["0xbeef", ""]);
+    }
+
+    #[test]
+    fn test_hint_registry_typed_routing() {
+        let mut routed = Vec::new();
+        let mut registry = HintRegistry::new();
+        registry.register_type(HintType::L1BlockHeader, |data| {
+            routed.push(data.to_vec());
+            Ok(())
+        });
+
+        registry.route_hint("l1-block-header 0xbeef").unwrap();
+        registry.route_hint("l1-block-header 0x").unwrap();
+        assert!(registry.route_hint("l1-block-header").is_err());
+        assert!(registry.route_hint("l1-block-header beef").is_err());
+        assert!(registry.route_hint("l2-block-header 0xbeef").is_err());
+        drop(registry);
+
+        assert_eq!(routed, [vec![0xbe, 0xef], vec![]]);
+    }
+}
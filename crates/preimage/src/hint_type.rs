@@ -0,0 +1,189 @@
+//! Contains the [HintType] and [Hint] types, which define the typed hint vocabulary of the OP Stack
+//! fault proof program and its text wire format.
+//!
+//! Hints are sent over the hint pipe as `<hint-type> 0x<hex-data>`, e.g.
+//! `l1-block-header 0xdeadbeef...`.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use anyhow::{anyhow, bail, Error};
+use core::{fmt::Display, str::FromStr};
+
+/// The [HintType] enum defines the hint types understood by the OP Stack fault proof program host.
+///
+/// <https://specs.optimism.io/experimental/fault-proof/index.html#hinting>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HintType {
+    /// A hint that specifies the block header of a layer 1 block.
+    L1BlockHeader,
+    /// A hint that specifies the transactions of a layer 1 block.
+    L1Transactions,
+    /// A hint that specifies the receipts of a layer 1 block.
+    L1Receipts,
+    /// A hint that specifies a blob in the layer 1 beacon chain.
+    L1Blob,
+    /// A hint that specifies a precompile call on layer 1, to be accelerated by the host.
+    L1Precompile,
+    /// A hint that specifies the block header of a layer 2 block.
+    L2BlockHeader,
+    /// A hint that specifies the transactions of a layer 2 block.
+    L2Transactions,
+    /// A hint that specifies a node in the layer 2 state trie.
+    L2StateNode,
+    /// A hint that specifies the code of a contract on layer 2.
+    L2Code,
+    /// A hint that specifies the output root of a block on layer 2.
+    L2Output,
+}
+
+impl HintType {
+    /// All [HintType] variants, in the order they are defined.
+    pub const ALL: [HintType; 10] = [
+        HintType::L1BlockHeader,
+        HintType::L1Transactions,
+        HintType::L1Receipts,
+        HintType::L1Blob,
+        HintType::L1Precompile,
+        HintType::L2BlockHeader,
+        HintType::L2Transactions,
+        HintType::L2StateNode,
+        HintType::L2Code,
+        HintType::L2Output,
+    ];
+
+    /// Returns the wire identifier of the [HintType].
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            HintType::L1BlockHeader => "l1-block-header",
+            HintType::L1Transactions => "l1-transactions",
+            HintType::L1Receipts => "l1-receipts",
+            HintType::L1Blob => "l1-blob",
+            HintType::L1Precompile => "l1-precompile",
+            HintType::L2BlockHeader => "l2-block-header",
+            HintType::L2Transactions => "l2-transactions",
+            HintType::L2StateNode => "l2-state-node",
+            HintType::L2Code => "l2-code",
+            HintType::L2Output => "l2-output",
+        }
+    }
+
+    /// Encodes the [HintType] with the concatenation of the passed data slices into the hint wire
+    /// format.
+    pub fn encode_with(&self, data: &[&[u8]]) -> String {
+        Hint::new(*self, data.concat()).encode()
+    }
+}
+
+impl Display for HintType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HintType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HintType::ALL
+            .into_iter()
+            .find(|hint_type| hint_type.as_str() == s)
+            .ok_or_else(|| anyhow!("Unknown hint type: {s}"))
+    }
+}
+
+/// A [Hint] is a typed hint, composed of a [HintType] and the data that accompanies it. Its
+/// [Display] and [FromStr] implementations convert to and from the hint wire format, and are the
+/// single source of truth for both the client and the host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// The type of the hint.
+    pub hint_type: HintType,
+    /// The data accompanying the hint.
+    pub data: Vec<u8>,
+}
+
+impl Hint {
+    /// Create a new [Hint] from a [HintType] and its data.
+    pub fn new(hint_type: HintType, data: Vec<u8>) -> Self {
+        Self { hint_type, data }
+    }
+
+    /// Parses a [Hint] from the hint wire format.
+    ///
+    /// # Returns
+    /// - `Ok(Hint)` if the hint has a known [HintType] and `0x`-prefixed hex data.
+    /// - `Err(_)` if the hint is malformed.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let (hint_type, data) =
+            s.split_once(' ').ok_or_else(|| anyhow!("Hint is missing data: {s}"))?;
+
+        Ok(Self { hint_type: hint_type.parse()?, data: Self::parse_data(data)? })
+    }
+
+    /// Parses the `0x`-prefixed hex data that follows the hint type in the hint wire format.
+    pub(crate) fn parse_data(data: &str) -> Result<Vec<u8>, Error> {
+        let Some(data) = data.strip_prefix("0x") else {
+            bail!("Hint data is missing the 0x prefix: {data}");
+        };
+        hex::decode(data).map_err(|e| anyhow!("Invalid hint data: {e}"))
+    }
+
+    /// Encodes the [Hint] into the hint wire format.
+    pub fn encode(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} 0x{}", self.hint_type, hex::encode(&self.data))
+    }
+}
+
+impl FromStr for Hint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Hint::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_hint_type_roundtrip() {
+        for hint_type in HintType::ALL {
+            assert_eq!(hint_type.to_string().parse::<HintType>().unwrap(), hint_type);
+        }
+        assert!("sha2-preimage".parse::<HintType>().is_err());
+    }
+
+    #[test]
+    fn test_hint_roundtrip() {
+        for hint_type in HintType::ALL {
+            let hint = Hint::new(hint_type, vec![0xde, 0xad, 0xbe, 0xef]);
+            let encoded = hint.encode();
+            assert_eq!(encoded, alloc::format!("{} 0xdeadbeef", hint_type.as_str()));
+            assert_eq!(Hint::parse(&encoded).unwrap(), hint);
+        }
+    }
+
+    #[test]
+    fn test_hint_encode_with() {
+        let encoded = HintType::L1Blob.encode_with(&[&[0xAA; 2], &[0xBB], &[]]);
+        assert_eq!(encoded, "l1-blob 0xaaaabb");
+    }
+
+    #[test]
+    fn test_hint_parse_invalid() {
+        assert!("l1-block-header".parse::<Hint>().is_err());
+        assert!("l1-block-header deadbeef".parse::<Hint>().is_err());
+        assert!("l1-block-header 0xnothex".parse::<Hint>().is_err());
+        assert!("unknown-hint 0xdeadbeef".parse::<Hint>().is_err());
+    }
+}
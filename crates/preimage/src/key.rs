@@ -1,8 +1,40 @@
 //! Contains the [PreimageKey] type, which is used to identify preimages that may be fetched from
 //! the preimage oracle.
 
+use core::{fmt::Display, str::FromStr};
+use sha3::{Digest, Keccak256};
+
+/// An error that occurs when parsing a [PreimageKey] or [PreimageKeyType].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreimageKeyError {
+    /// The type byte does not correspond to a known [PreimageKeyType].
+    InvalidKeyType(u8),
+    /// The key is not valid hex.
+    InvalidHex,
+    /// The key has an odd number of hex digits.
+    OddLength,
+    /// The key does not have a length of 32 bytes. The argument is the actual length.
+    InvalidLength(usize),
+}
+
+impl Display for PreimageKeyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PreimageKeyError::InvalidKeyType(t) => write!(f, "Invalid preimage key type: {}", t),
+            PreimageKeyError::InvalidHex => write!(f, "Invalid hex in preimage key"),
+            PreimageKeyError::OddLength => write!(f, "Odd number of hex digits in preimage key"),
+            PreimageKeyError::InvalidLength(len) => {
+                write!(f, "Invalid preimage key length: expected 32 bytes, got {}", len)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PreimageKeyError {}
+
 /// <https://specs.optimism.io/experimental/fault-proof/index.html#pre-image-key-types>
-#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
 #[repr(u8)]
 pub enum PreimageKeyType {
     /// Local key types are local to a given instance of a fault-proof and context dependent.
@@ -21,6 +53,27 @@ pub enum PreimageKeyType {
     /// `keccak256(commitment ++ z)`, and then the high-order byte of the digest is set to the
     /// type byte.
     Blob = 5,
+    /// Precompile key types are global and context independent. Precompile keys are constructed as
+    /// `keccak256(precompile_addr ++ input)`, and then the high-order byte of the digest is set to
+    /// the type byte. The preimage is the result of running the precompile on the host.
+    Precompile = 6,
+}
+
+impl TryFrom<u8> for PreimageKeyType {
+    type Error = PreimageKeyError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        let key_type = match value {
+            1 => PreimageKeyType::Local,
+            2 => PreimageKeyType::Keccak256,
+            3 => PreimageKeyType::GlobalGeneric,
+            4 => PreimageKeyType::Sha256,
+            5 => PreimageKeyType::Blob,
+            6 => PreimageKeyType::Precompile,
+            _ => return Err(PreimageKeyError::InvalidKeyType(value)),
+        };
+        Ok(key_type)
+    }
 }
 
 /// A preimage key is a 32-byte value that identifies a preimage that may be fetched from the
@@ -31,7 +84,7 @@ pub enum PreimageKeyType {
 /// |---------|-------------|
 /// | [0, 1)  | Type byte   |
 /// | [1, 32) | Data        |
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct PreimageKey {
     data: [u8; 31],
     key_type: PreimageKeyType,
@@ -54,6 +107,44 @@ impl PreimageKey {
         Self { data, key_type: PreimageKeyType::Local }
     }
 
+    /// Creates a new blob [PreimageKey] for a single field element of a blob, from the blob's
+    /// 48-byte KZG commitment and the 32-byte big-endian root of unity `z` that the field element
+    /// is evaluated at. The key is `keccak256(commitment ++ z)`, with the type byte set to
+    /// [PreimageKeyType::Blob].
+    pub fn new_blob(commitment: &[u8; 48], z: &[u8; 32]) -> Self {
+        let digest = Keccak256::new().chain_update(commitment).chain_update(z).finalize();
+        Self::new(digest.into(), PreimageKeyType::Blob)
+    }
+
+    /// Creates a new precompile [PreimageKey] from the 20-byte address of the precompile and its
+    /// input. The key is `keccak256(address ++ input)`, with the type byte set to
+    /// [PreimageKeyType::Precompile].
+    pub fn new_precompile(address: &[u8; 20], input: &[u8]) -> Self {
+        let digest = Keccak256::new().chain_update(address).chain_update(input).finalize();
+        Self::new(digest.into(), PreimageKeyType::Precompile)
+    }
+
+    /// Localizes the [PreimageKey] to an on-chain dispute context, as done by the `PreimageOracle`
+    /// contract. The localized key is `keccak256(key ++ caller ++ local_context)`, where `caller`
+    /// is left-padded to 32 bytes, with the type byte set to [PreimageKeyType::Local].
+    ///
+    /// Only [PreimageKeyType::Local] keys are localized; global keys are context independent and
+    /// are returned as-is.
+    pub fn localize(&self, caller: &[u8; 20], local_context: &[u8; 32]) -> Self {
+        if self.key_type != PreimageKeyType::Local {
+            return *self;
+        }
+
+        let mut caller_word = [0u8; 32];
+        caller_word[12..].copy_from_slice(caller);
+        let digest = Keccak256::new()
+            .chain_update(<[u8; 32]>::from(*self))
+            .chain_update(caller_word)
+            .chain_update(local_context)
+            .finalize();
+        Self::new(digest.into(), PreimageKeyType::Local)
+    }
+
     /// Returns the [PreimageKeyType] for the [PreimageKey].
     pub fn key_type(&self) -> PreimageKeyType {
         self.key_type
@@ -69,13 +160,72 @@ impl From<PreimageKey> for [u8; 32] {
     }
 }
 
+impl TryFrom<[u8; 32]> for PreimageKey {
+    type Error = PreimageKeyError;
+
+    fn try_from(value: [u8; 32]) -> Result<Self, Self::Error> {
+        let key_type = PreimageKeyType::try_from(value[0])?;
+        Ok(Self::new(value, key_type))
+    }
+}
+
+impl Display for PreimageKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut buf = [0u8; 64];
+        hex::encode_to_slice(<[u8; 32]>::from(*self), &mut buf).map_err(|_| core::fmt::Error)?;
+        write!(f, "0x{}", core::str::from_utf8(&buf).map_err(|_| core::fmt::Error)?)
+    }
+}
+
+impl FromStr for PreimageKey {
+    type Err = PreimageKeyError;
+
+    /// Parses a [PreimageKey] from a hex string, with or without a `0x` prefix.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        if s.len() & 1 == 1 {
+            return Err(PreimageKeyError::OddLength);
+        }
+        if s.len() != 64 {
+            return Err(PreimageKeyError::InvalidLength(s.len() / 2));
+        }
+
+        let mut key = [0u8; 32];
+        hex::decode_to_slice(s, &mut key).map_err(|_| PreimageKeyError::InvalidHex)?;
+        Self::try_from(key)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PreimageKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PreimageKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = <alloc::string::String as serde::Deserialize<'de>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use alloc::string::ToString;
 
     #[test]
     fn test_preimage_keys() {
-        let types = [PreimageKeyType::Local, PreimageKeyType::Keccak256];
+        let types = [
+            PreimageKeyType::Local,
+            PreimageKeyType::Keccak256,
+            PreimageKeyType::GlobalGeneric,
+            PreimageKeyType::Sha256,
+            PreimageKeyType::Blob,
+            PreimageKeyType::Precompile,
+        ];
 
         for key_type in types {
             let key = PreimageKey::new([0xFFu8; 32], key_type);
@@ -87,4 +237,90 @@ mod test {
             assert_eq!(actual, rendered_key);
         }
     }
+
+    #[test]
+    fn test_localize_key() {
+        let (caller, local_context) = ([0xCC; 20], [0xDD; 32]);
+        let key = PreimageKey::new_local(4);
+        let localized = key.localize(&caller, &local_context);
+        assert_eq!(localized.key_type(), PreimageKeyType::Local);
+
+        let mut preimage = [0u8; 96];
+        preimage[..32].copy_from_slice(&<[u8; 32]>::from(key));
+        preimage[44..64].copy_from_slice(&caller);
+        preimage[64..].copy_from_slice(&local_context);
+        let mut expected: [u8; 32] = Keccak256::digest(preimage).into();
+        expected[0] = PreimageKeyType::Local as u8;
+        assert_eq!(<[u8; 32]>::from(localized), expected);
+
+        let other = key.localize(&[0xEE; 20], &local_context);
+        assert_ne!(<[u8; 32]>::from(other), <[u8; 32]>::from(localized));
+
+        let global = PreimageKey::new([0xAA; 32], PreimageKeyType::Keccak256);
+        let rendered: [u8; 32] = global.into();
+        assert_eq!(<[u8; 32]>::from(global.localize(&caller, &local_context)), rendered);
+    }
+
+    #[test]
+    fn test_preimage_key_roundtrip() {
+        let key = PreimageKey::new([0xAAu8; 32], PreimageKeyType::Sha256);
+        let rendered: [u8; 32] = key.into();
+        let parsed = PreimageKey::try_from(rendered).unwrap();
+        assert_eq!(parsed.key_type(), PreimageKeyType::Sha256);
+        assert_eq!(<[u8; 32]>::from(parsed), rendered);
+    }
+
+    #[test]
+    fn test_preimage_key_invalid_type() {
+        let mut rendered = [0u8; 32];
+        rendered[0] = 0xFF;
+        assert_eq!(PreimageKey::try_from(rendered), Err(PreimageKeyError::InvalidKeyType(0xFF)));
+        assert_eq!(PreimageKeyType::try_from(0), Err(PreimageKeyError::InvalidKeyType(0)));
+    }
+
+    #[test]
+    fn test_preimage_key_display_from_str() {
+        let key = PreimageKey::new_local(0xFACADE);
+        let s = key.to_string();
+        assert_eq!(s, "0x0100000000000000000000000000000000000000000000000000000000facade");
+        assert_eq!(s.parse::<PreimageKey>(), Ok(key));
+        assert_eq!(s[2..].parse::<PreimageKey>(), Ok(key));
+
+        assert_eq!("0x01".parse::<PreimageKey>(), Err(PreimageKeyError::InvalidLength(1)));
+        assert_eq!("0x012".parse::<PreimageKey>(), Err(PreimageKeyError::OddLength));
+        assert_eq!(s[..65].parse::<PreimageKey>(), Err(PreimageKeyError::OddLength));
+        assert_eq!(
+            "0xzz00000000000000000000000000000000000000000000000000000000facade"
+                .parse::<PreimageKey>(),
+            Err(PreimageKeyError::InvalidHex)
+        );
+        assert_eq!(
+            "0x0700000000000000000000000000000000000000000000000000000000facade"
+                .parse::<PreimageKey>(),
+            Err(PreimageKeyError::InvalidKeyType(7))
+        );
+    }
+
+    #[test]
+    fn test_preimage_key_hash() {
+        let mut set = hashbrown::HashSet::new();
+        set.insert(PreimageKey::new_local(1));
+        set.insert(PreimageKey::new_local(1));
+        set.insert(PreimageKey::new([1; 32], PreimageKeyType::Keccak256));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_preimage_key_serde() {
+        let key = PreimageKey::new([0xAB; 32], PreimageKeyType::Sha256);
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, alloc::format!("\"{}\"", key));
+        assert_eq!(serde_json::from_str::<PreimageKey>(&json).unwrap(), key);
+        assert!(serde_json::from_str::<PreimageKey>("\"0x00\"").is_err());
+
+        // Escaped strings can't be borrowed from the input.
+        let escaped = json.replace('a', "\\u0061");
+        assert_eq!(serde_json::from_str::<PreimageKey>(&escaped).unwrap(), key);
+    }
 }
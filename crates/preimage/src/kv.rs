@@ -0,0 +1,269 @@
+//! Contains the [KeyValueStore] trait and its implementations, which form the storage layer of a
+//! host that serves preimages to a client program.
+
+use crate::{PreimageFetcher, PreimageKey, PreimageKeyType};
+use alloc::vec::Vec;
+use anyhow::{anyhow, Result};
+use hashbrown::HashMap;
+
+/// A [KeyValueStore] is a store of preimages, keyed by their [PreimageKey].
+pub trait KeyValueStore {
+    /// Get the preimage for the given [PreimageKey].
+    ///
+    /// # Returns
+    /// - `Ok(Some(_))` if the preimage exists in the store.
+    /// - `Ok(None)` if the preimage does not exist in the store.
+    /// - `Err(_)` if the store could not be read.
+    fn get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>>;
+
+    /// Set the preimage for the given [PreimageKey], overwriting any existing preimage.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the preimage was successfully stored.
+    /// - `Err(_)` if the preimage could not be stored.
+    fn set(&mut self, key: PreimageKey, value: Vec<u8>) -> Result<()>;
+}
+
+/// A [KeyValueFetcher] is a [PreimageFetcher] that serves preimages to an [crate::OracleServer]
+/// from a [KeyValueStore]. Missing preimages are reported as errors.
+#[derive(Debug, Default, Clone)]
+pub struct KeyValueFetcher<S: KeyValueStore> {
+    store: S,
+}
+
+impl<S: KeyValueStore> KeyValueFetcher<S> {
+    /// Create a new [KeyValueFetcher] that serves preimages from `store`.
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Returns a reference to the underlying [KeyValueStore].
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Consumes the [KeyValueFetcher], returning the underlying [KeyValueStore].
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
+
+impl<S: KeyValueStore> PreimageFetcher for KeyValueFetcher<S> {
+    fn get_preimage(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+        self.store.get(key)?.ok_or_else(|| anyhow!("Preimage not found for key: {}", key))
+    }
+}
+
+/// A [MemoryKeyValueStore] is a simple in-memory [KeyValueStore].
+#[derive(Debug, Default, Clone)]
+pub struct MemoryKeyValueStore {
+    store: HashMap<PreimageKey, Vec<u8>>,
+}
+
+impl MemoryKeyValueStore {
+    /// Create a new, empty [MemoryKeyValueStore].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl KeyValueStore for MemoryKeyValueStore {
+    fn get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        Ok(self.store.get(&key).cloned())
+    }
+
+    fn set(&mut self, key: PreimageKey, value: Vec<u8>) -> Result<()> {
+        self.store.insert(key, value);
+        Ok(())
+    }
+}
+
+/// A [SplitKeyValueStore] routes [PreimageKeyType::Local] keys to one [KeyValueStore], usually a
+/// provider of the client program's boot information, and all other keys to another.
+#[derive(Debug, Clone)]
+pub struct SplitKeyValueStore<L, G>
+where
+    L: KeyValueStore,
+    G: KeyValueStore,
+{
+    /// The store for [PreimageKeyType::Local] keys.
+    local_store: L,
+    /// The store for global keys.
+    global_store: G,
+}
+
+impl<L, G> SplitKeyValueStore<L, G>
+where
+    L: KeyValueStore,
+    G: KeyValueStore,
+{
+    /// Create a new [SplitKeyValueStore] from a store for local keys and a store for global keys.
+    pub fn new(local_store: L, global_store: G) -> Self {
+        Self { local_store, global_store }
+    }
+}
+
+impl<L, G> KeyValueStore for SplitKeyValueStore<L, G>
+where
+    L: KeyValueStore,
+    G: KeyValueStore,
+{
+    fn get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        match key.key_type() {
+            PreimageKeyType::Local => self.local_store.get(key),
+            _ => self.global_store.get(key),
+        }
+    }
+
+    fn set(&mut self, key: PreimageKey, value: Vec<u8>) -> Result<()> {
+        match key.key_type() {
+            PreimageKeyType::Local => self.local_store.set(key, value),
+            _ => self.global_store.set(key, value),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub use disk::DiskKeyValueStore;
+
+#[cfg(feature = "std")]
+mod disk {
+    use super::KeyValueStore;
+    use crate::PreimageKey;
+    use alloc::{format, vec::Vec};
+    use anyhow::{anyhow, Result};
+    use std::{
+        fs,
+        io::{ErrorKind, Write},
+        path::{Path, PathBuf},
+    };
+    use tempfile::NamedTempFile;
+
+    /// A [DiskKeyValueStore] is a directory-backed [KeyValueStore], with one file per key. The
+    /// layout is compatible with `op-program`'s `--datadir`: each preimage is stored hex-encoded in
+    /// a file named `0x<key>.txt`.
+    #[derive(Debug, Clone)]
+    pub struct DiskKeyValueStore {
+        data_directory: PathBuf,
+    }
+
+    impl DiskKeyValueStore {
+        /// Create a new [DiskKeyValueStore] in the given directory, creating it if it does not
+        /// exist.
+        pub fn new(data_directory: impl Into<PathBuf>) -> Result<Self> {
+            let data_directory = data_directory.into();
+            fs::create_dir_all(&data_directory)
+                .map_err(|e| anyhow!("Failed to create data directory: {e}"))?;
+            Ok(Self { data_directory })
+        }
+
+        /// Returns the data directory of the store.
+        pub fn data_directory(&self) -> &Path {
+            &self.data_directory
+        }
+
+        /// Returns the path of the file that the preimage for `key` is stored in.
+        fn path_for(&self, key: PreimageKey) -> PathBuf {
+            self.data_directory.join(format!("{key}.txt"))
+        }
+    }
+
+    impl KeyValueStore for DiskKeyValueStore {
+        fn get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+            let encoded = match fs::read_to_string(self.path_for(key)) {
+                Ok(encoded) => encoded,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(anyhow!("Failed to read preimage for key {key}: {e}")),
+            };
+            let value = hex::decode(encoded.trim())
+                .map_err(|e| anyhow!("Invalid preimage for key {key}: {e}"))?;
+            Ok(Some(value))
+        }
+
+        fn set(&mut self, key: PreimageKey, value: Vec<u8>) -> Result<()> {
+            // Write to a uniquely named temporary file in the data directory first, and then move
+            // it into place, so that readers never observe a partially written preimage
+            // and concurrent writers never share a temporary file.
+            let mut file = NamedTempFile::new_in(&self.data_directory)
+                .map_err(|e| anyhow!("Failed to create temporary file for key {key}: {e}"))?;
+            file.write_all(hex::encode(value).as_bytes())
+                .map_err(|e| anyhow!("Failed to write preimage for key {key}: {e}"))?;
+            file.persist(self.path_for(key))
+                .map_err(|e| anyhow!("Failed to write preimage for key {key}: {e}"))?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_memory_kv_store() {
+        let mut store = MemoryKeyValueStore::new();
+        let key = PreimageKey::new([0xAA; 32], PreimageKeyType::Keccak256);
+        assert_eq!(store.get(key).unwrap(), None);
+
+        store.set(key, vec![1, 2, 3]).unwrap();
+        assert_eq!(store.get(key).unwrap(), Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_kv_fetcher() {
+        let mut fetcher = KeyValueFetcher::new(MemoryKeyValueStore::new());
+        let key = PreimageKey::new([0xAA; 32], PreimageKeyType::Keccak256);
+        assert!(fetcher.get_preimage(key).is_err());
+
+        let mut store = fetcher.into_inner();
+        store.set(key, vec![1, 2, 3]).unwrap();
+        let mut fetcher = KeyValueFetcher::new(store);
+        assert_eq!(fetcher.get_preimage(key).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_split_kv_store() {
+        let mut store =
+            SplitKeyValueStore::new(MemoryKeyValueStore::new(), MemoryKeyValueStore::new());
+        let local_key = PreimageKey::new_local(1);
+        let global_key = PreimageKey::new([0xBB; 32], PreimageKeyType::Sha256);
+
+        store.set(local_key, vec![1]).unwrap();
+        store.set(global_key, vec![2]).unwrap();
+
+        assert_eq!(store.local_store.get(local_key).unwrap(), Some(vec![1]));
+        assert_eq!(store.local_store.get(global_key).unwrap(), None);
+        assert_eq!(store.global_store.get(global_key).unwrap(), Some(vec![2]));
+        assert_eq!(store.global_store.get(local_key).unwrap(), None);
+        assert_eq!(store.get(local_key).unwrap(), Some(vec![1]));
+        assert_eq!(store.get(global_key).unwrap(), Some(vec![2]));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_disk_kv_store() {
+        extern crate std;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = DiskKeyValueStore::new(dir.path()).unwrap();
+        let key = PreimageKey::new([0xCC; 32], PreimageKeyType::Keccak256);
+        assert_eq!(store.get(key).unwrap(), None);
+
+        store.set(key, vec![0xDE, 0xAD]).unwrap();
+        assert_eq!(store.get(key).unwrap(), Some(vec![0xDE, 0xAD]));
+
+        // The preimage is stored hex-encoded in a file named after the key.
+        let path = dir.path().join(alloc::format!("{key}.txt"));
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "dead");
+
+        // A new store over the same directory sees the same preimages.
+        let reopened = DiskKeyValueStore::new(dir.path()).unwrap();
+        assert_eq!(reopened.get(key).unwrap(), Some(vec![0xDE, 0xAD]));
+
+        // Overwriting a preimage leaves no temporary files behind.
+        store.set(key, vec![0xBE, 0xEF]).unwrap();
+        assert_eq!(reopened.get(key).unwrap(), Some(vec![0xBE, 0xEF]));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -6,17 +6,70 @@
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 mod key;
-pub use key::{PreimageKey, PreimageKeyType};
+pub use key::{PreimageKey, PreimageKeyError, PreimageKeyType};
 
 mod oracle;
-pub use oracle::OracleReader;
+pub use oracle::{OracleReader, OracleServer, PreimageStream};
 
 mod hint;
-pub use hint::HintWriter;
+pub use hint::{HintReader, HintRegistry, HintWriter};
+
+mod hint_type;
+pub use hint_type::{Hint, HintType};
+
+mod cache;
+pub use cache::CachingOracle;
+
+mod blob;
+pub use blob::{get_blob, root_of_unity, Blob, BLOB_SIZE, FIELD_ELEMENTS_PER_BLOB};
+
+mod lpp;
+pub use lpp::{
+    LppLeaf, LppProof, LppTree, StateMatrix, KECCAK_BLOCK_SIZE, LPP_TREE_DEPTH, MAX_LPP_LEAVES,
+};
+
+mod kv;
+#[cfg(feature = "std")]
+pub use kv::DiskKeyValueStore;
+pub use kv::{KeyValueFetcher, KeyValueStore, MemoryKeyValueStore, SplitKeyValueStore};
+
+mod boot;
+pub use boot::{
+    BootInfo, L1_HEAD_KEY, L2_CHAIN_CONFIG_KEY, L2_CHAIN_ID_KEY, L2_CLAIM_BLOCK_NUMBER_KEY,
+    L2_CLAIM_KEY, L2_OUTPUT_ROOT_KEY, L2_ROLLUP_CONFIG_KEY,
+};
+
+mod part;
+pub use part::PreimagePart;
+
+mod precompile;
+pub use precompile::{call_precompile, PrecompileOutput};
+
+mod bundle;
+pub use bundle::{
+    BundleOracle, PreimageBundle, RecordingHintWriter, RecordingOracle, WitnessRecorder,
+};
+
+mod verify;
+pub use verify::{PreimageVerificationError, VerifyingOracle};
 
 mod pipe;
 pub use pipe::PipeHandle;
 
+mod channel;
+pub use channel::{ChannelClosed, DuplexChannel};
+
+#[cfg(feature = "tokio")]
+mod async_server;
+#[cfg(feature = "tokio")]
+pub use async_server::{AsyncHintReader, AsyncHintRouter, AsyncOracleServer, AsyncPreimageFetcher};
+
 mod traits;
-pub use traits::{HintWriterClient, PreimageOracleClient};
+pub use traits::{
+    Channel, HintReaderServer, HintRouter, HintWriterClient, PreimageFetcher, PreimageOracleClient,
+    PreimageOracleServer,
+};
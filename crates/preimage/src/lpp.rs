@@ -0,0 +1,260 @@
+//! Contains utilities for preparing large preimage proposals (LPPs) for the on-chain
+//! `PreimageOracle` contract.
+//!
+//! Keccak256 preimages that are too large to be submitted in a single transaction are absorbed
+//! into the keccak sponge one 136-byte block at a time. Each absorbed block forms an [LppLeaf],
+//! which commits to the block and to the sponge state after absorbing it. The leaves are
+//! merklized into a binary merkle tree of depth [LPP_TREE_DEPTH], which allows challengers to prove
+//! that a single absorption step was performed incorrectly.
+
+use alloc::{vec, vec::Vec};
+use anyhow::{bail, Result};
+use sha3::{Digest, Keccak256};
+
+/// The keccak256 sponge rate, in bytes. Preimages are absorbed in blocks of this size.
+pub const KECCAK_BLOCK_SIZE: usize = 136;
+
+/// The depth of the large preimage proposal leaf merkle tree.
+pub const LPP_TREE_DEPTH: usize = 16;
+
+/// The maximum number of leaves in a large preimage proposal.
+pub const MAX_LPP_LEAVES: usize = 1 << LPP_TREE_DEPTH;
+
+/// A merkle proof for an [LppLeaf], composed of the sibling hashes from the leaf to the root.
+pub type LppProof = [[u8; 32]; LPP_TREE_DEPTH];
+
+/// The keccak-f\[1600\] state matrix, as 25 64-bit lanes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StateMatrix([u64; 25]);
+
+impl StateMatrix {
+    /// Returns the lanes of the [StateMatrix].
+    pub fn lanes(&self) -> &[u64; 25] {
+        &self.0
+    }
+
+    /// Absorbs a single padded block into the sponge, and applies the keccak-f\[1600\]
+    /// permutation.
+    pub fn absorb(&mut self, block: &[u8; KECCAK_BLOCK_SIZE]) {
+        for (lane, chunk) in self.0.iter_mut().zip(block.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(chunk.try_into().expect("Chunk size is 8"));
+        }
+        keccak::f1600(&mut self.0);
+    }
+
+    /// Returns the 32-byte digest that would be squeezed out of the sponge in its current state.
+    pub fn squeeze(&self) -> [u8; 32] {
+        let mut digest = [0u8; 32];
+        for (chunk, lane) in digest.chunks_exact_mut(8).zip(self.0.iter()) {
+            chunk.copy_from_slice(&lane.to_le_bytes());
+        }
+        digest
+    }
+
+    /// Returns the commitment to the [StateMatrix], which is the `keccak256` digest of the
+    /// ABI-encoded `uint64[25]` lanes.
+    pub fn commitment(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        for lane in self.0.iter() {
+            let mut word = [0u8; 32];
+            word[24..].copy_from_slice(&lane.to_be_bytes());
+            hasher.update(word);
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// A leaf of a large preimage proposal: a single padded block of the preimage, its index, and the
+/// sponge state after absorbing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LppLeaf {
+    /// The padded input block.
+    pub input: [u8; KECCAK_BLOCK_SIZE],
+    /// The index of the block in the preimage.
+    pub index: u64,
+    /// The sponge state after absorbing the input block.
+    pub state: StateMatrix,
+}
+
+impl LppLeaf {
+    /// Returns the commitment to the sponge state after absorbing the input block.
+    pub fn state_commitment(&self) -> [u8; 32] {
+        self.state.commitment()
+    }
+
+    /// Returns the hash of the leaf, `keccak256(input ++ uint256(index) ++ state_commitment)`.
+    pub fn hash(&self) -> [u8; 32] {
+        let mut index = [0u8; 32];
+        index[24..].copy_from_slice(&self.index.to_be_bytes());
+        Keccak256::new()
+            .chain_update(self.input)
+            .chain_update(index)
+            .chain_update(self.state_commitment())
+            .finalize()
+            .into()
+    }
+}
+
+/// The leaves of a large preimage proposal and the merkle tree built over them.
+#[derive(Debug, Clone)]
+pub struct LppTree {
+    /// The leaves of the proposal, in absorption order.
+    leaves: Vec<LppLeaf>,
+    /// The levels of the merkle tree, from the leaf hashes up to (but excluding) the root. Nodes
+    /// that are missing from a level are implicitly the zero hash of that level.
+    levels: Vec<Vec<[u8; 32]>>,
+    /// The zero hashes of each level of the merkle tree.
+    zero_hashes: [[u8; 32]; LPP_TREE_DEPTH + 1],
+}
+
+impl LppTree {
+    /// Splits a preimage into padded blocks, absorbs them into a keccak256 sponge while recording
+    /// the intermediate states, and builds the leaf merkle tree.
+    ///
+    /// The preimage is padded with the keccak `pad10*1` rule. If the preimage length is a multiple
+    /// of [KECCAK_BLOCK_SIZE], the padding forms an additional final block.
+    pub fn from_preimage(preimage: &[u8]) -> Result<Self> {
+        let num_leaves = preimage.len() / KECCAK_BLOCK_SIZE + 1;
+        if num_leaves > MAX_LPP_LEAVES {
+            bail!("Preimage of size {} exceeds the maximum LPP size", preimage.len());
+        }
+
+        let mut state = StateMatrix::default();
+        let mut leaves = Vec::with_capacity(num_leaves);
+        for index in 0..num_leaves {
+            let start = index * KECCAK_BLOCK_SIZE;
+            let end = preimage.len().min(start + KECCAK_BLOCK_SIZE);
+
+            let mut input = [0u8; KECCAK_BLOCK_SIZE];
+            input[..end - start].copy_from_slice(&preimage[start..end]);
+            if index == num_leaves - 1 {
+                input[end - start] ^= 0x01;
+                input[KECCAK_BLOCK_SIZE - 1] ^= 0x80;
+            }
+
+            state.absorb(&input);
+            leaves.push(LppLeaf { input, index: index as u64, state });
+        }
+
+        let mut zero_hashes = [[0u8; 32]; LPP_TREE_DEPTH + 1];
+        for i in 1..=LPP_TREE_DEPTH {
+            zero_hashes[i] = hash_pair(&zero_hashes[i - 1], &zero_hashes[i - 1]);
+        }
+
+        let mut levels = vec![leaves.iter().map(LppLeaf::hash).collect::<Vec<_>>()];
+        for depth in 0..LPP_TREE_DEPTH - 1 {
+            let level = levels[depth]
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&zero_hashes[depth])))
+                .collect();
+            levels.push(level);
+        }
+
+        Ok(Self { leaves, levels, zero_hashes })
+    }
+
+    /// Returns the leaves of the proposal, in absorption order.
+    pub fn leaves(&self) -> &[LppLeaf] {
+        &self.leaves
+    }
+
+    /// Returns the sponge state before absorbing the leaf at `index`, if the leaf exists.
+    pub fn pre_state(&self, index: usize) -> Option<StateMatrix> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+        Some(index.checked_sub(1).map(|i| self.leaves[i].state).unwrap_or_default())
+    }
+
+    /// Returns the root of the leaf merkle tree.
+    pub fn root(&self) -> [u8; 32] {
+        let top = &self.levels[LPP_TREE_DEPTH - 1];
+        hash_pair(&top[0], top.get(1).unwrap_or(&self.zero_hashes[LPP_TREE_DEPTH - 1]))
+    }
+
+    /// Returns the merkle proof for the leaf at `index`, if the leaf exists.
+    pub fn proof(&self, index: usize) -> Option<LppProof> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+
+        let mut proof = [[0u8; 32]; LPP_TREE_DEPTH];
+        for (depth, sibling) in proof.iter_mut().enumerate() {
+            let sibling_index = (index >> depth) ^ 1;
+            *sibling = *self.levels[depth].get(sibling_index).unwrap_or(&self.zero_hashes[depth]);
+        }
+        Some(proof)
+    }
+
+    /// Verifies a merkle proof for the leaf with `leaf_hash` at `index` against `root`.
+    pub fn verify_proof(
+        root: &[u8; 32],
+        index: usize,
+        leaf_hash: &[u8; 32],
+        proof: &LppProof,
+    ) -> bool {
+        let node = proof.iter().enumerate().fold(*leaf_hash, |node, (depth, sibling)| {
+            if (index >> depth) & 1 == 0 {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            }
+        });
+        &node == root
+    }
+}
+
+/// Returns `keccak256(left ++ right)`.
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    Keccak256::new().chain_update(left).chain_update(right).finalize().into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lpp_sponge_matches_keccak256() {
+        for len in [0, 1, 135, 136, 137, 272, 1000] {
+            let preimage = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+            let tree = LppTree::from_preimage(&preimage).unwrap();
+
+            assert_eq!(tree.leaves().len(), len / KECCAK_BLOCK_SIZE + 1);
+            let final_state = tree.leaves().last().unwrap().state;
+            let expected: [u8; 32] = Keccak256::digest(&preimage).into();
+            assert_eq!(final_state.squeeze(), expected);
+        }
+    }
+
+    #[test]
+    fn test_lpp_pre_states() {
+        let tree = LppTree::from_preimage(&[0xAA; 300]).unwrap();
+        assert_eq!(tree.pre_state(0), Some(StateMatrix::default()));
+        assert_eq!(tree.pre_state(1), Some(tree.leaves()[0].state));
+        assert_eq!(tree.pre_state(2), Some(tree.leaves()[1].state));
+        assert_eq!(tree.pre_state(3), None);
+    }
+
+    #[test]
+    fn test_lpp_zero_hashes() {
+        let tree = LppTree::from_preimage(&[]).unwrap();
+        assert_eq!(
+            hex::encode(tree.zero_hashes[1]),
+            "ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5"
+        );
+    }
+
+    #[test]
+    fn test_lpp_proofs() {
+        for len in [0, 136 * 2, 136 * 5 + 3] {
+            let tree = LppTree::from_preimage(&alloc::vec![0x11; len]).unwrap();
+            let root = tree.root();
+            for (index, leaf) in tree.leaves().iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(LppTree::verify_proof(&root, index, &leaf.hash(), &proof));
+                assert!(!LppTree::verify_proof(&root, index + 1, &leaf.hash(), &proof));
+            }
+            assert!(tree.proof(tree.leaves().len()).is_none());
+        }
+    }
+}
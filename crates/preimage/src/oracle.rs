@@ -1,17 +1,20 @@
-use crate::{traits::PreimageOracleClient, PipeHandle, PreimageKey};
+use crate::{
+    traits::{PreimageFetcher, PreimageOracleClient, PreimageOracleServer},
+    Channel, PipeHandle, PreimageKey,
+};
 use alloc::vec::Vec;
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 
 /// An [OracleReader] is a high-level interface to the preimage oracle.
 #[derive(Debug, Clone, Copy)]
-pub struct OracleReader {
-    pipe_handle: PipeHandle,
+pub struct OracleReader<C: Channel = PipeHandle> {
+    channel: C,
 }
 
-impl OracleReader {
-    /// Create a new [OracleReader] from a [PipeHandle].
-    pub fn new(pipe_handle: PipeHandle) -> Self {
-        Self { pipe_handle }
+impl<C: Channel> OracleReader<C> {
+    /// Create a new [OracleReader] from a [Channel], such as a [PipeHandle].
+    pub fn new(channel: C) -> Self {
+        Self { channel }
     }
 
     /// Set the preimage key for the global oracle reader. This will overwrite any existing key, and
@@ -20,16 +23,104 @@ impl OracleReader {
     fn write_key(&mut self, key: PreimageKey) -> Result<usize> {
         // Write the key to the host so that it can prepare the preimage.
         let key_bytes: [u8; 32] = key.into();
-        self.pipe_handle.write(&key_bytes)?;
+        self.channel.write_all(&key_bytes)?;
 
         // Read the length prefix and reset the cursor.
         let mut length_buffer = [0u8; 8];
-        self.pipe_handle.read_exact(&mut length_buffer)?;
+        self.channel.read_exact(&mut length_buffer)?;
         Ok(u64::from_be_bytes(length_buffer) as usize)
     }
+
+    /// Request the preimage for `key` from the host without reading its body. The returned
+    /// [PreimageStream] exposes the length of the preimage up front, and lets the caller read the
+    /// body in chunks, so that large preimages never have to be held in memory in their entirety.
+    ///
+    /// # Returns
+    /// - `Ok(PreimageStream)` if the host responded with the length of the preimage.
+    /// - `Err(_)` if the request could not be made.
+    pub fn stream(&mut self, key: PreimageKey) -> Result<PreimageStream<'_, C>> {
+        let length = self.write_key(key)?;
+        Ok(PreimageStream { channel: &self.channel, length, remaining: length })
+    }
 }
 
-impl PreimageOracleClient for OracleReader {
+/// A [PreimageStream] is an in-progress read of a single preimage's body, created by
+/// [OracleReader::stream].
+///
+/// The body must be consumed in its entirety before the next preimage can be requested. If the
+/// stream is dropped early, the unread remainder of the body is drained from the channel.
+#[derive(Debug)]
+pub struct PreimageStream<'a, C: Channel> {
+    /// The channel that the body is read from.
+    channel: &'a C,
+    /// The total length of the preimage.
+    length: usize,
+    /// The number of bytes of the body that have not yet been read.
+    remaining: usize,
+}
+
+impl<C: Channel> PreimageStream<'_, C> {
+    /// Returns the total length of the preimage.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Returns `true` if the preimage is empty.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Returns the number of bytes of the body that have not yet been read.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Reads the next chunk of the body into `buf`, filling it unless fewer bytes remain.
+    ///
+    /// # Returns
+    /// - `Ok(n)` with the number of bytes read, which is `0` once the body is exhausted.
+    /// - `Err(_)` if the channel could not be read from.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = buf.len().min(self.remaining);
+        self.channel.read_exact(&mut buf[..n])?;
+        self.remaining -= n;
+        Ok(n)
+    }
+
+    /// Reads the rest of the body in chunks of up to `buf.len()` bytes, passing each chunk to
+    /// `sink`.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the body was read in its entirety and accepted by the sink.
+    /// - `Err(_)` if the channel could not be read from, or the sink returned an error.
+    pub fn read_into<F>(mut self, buf: &mut [u8], mut sink: F) -> Result<()>
+    where
+        F: FnMut(&[u8]) -> Result<()>,
+    {
+        if buf.is_empty() && self.remaining > 0 {
+            bail!("Cannot stream a preimage into an empty buffer");
+        }
+        while self.remaining > 0 {
+            let n = self.read(buf)?;
+            sink(&buf[..n])?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: Channel> Drop for PreimageStream<'_, C> {
+    fn drop(&mut self) {
+        // Drain the unread remainder of the body to keep the channel in sync for the next request.
+        let mut buf = [0u8; 256];
+        while self.remaining > 0 {
+            if self.read(&mut buf).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<C: Channel> PreimageOracleClient for OracleReader<C> {
     /// Get the data corresponding to the currently set key from the host. Return the data in a new
     /// heap allocated `Vec<u8>`
     fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
@@ -37,7 +128,7 @@ impl PreimageOracleClient for OracleReader {
         let mut data_buffer = alloc::vec![0; length];
 
         // Grab a read lock on the preimage pipe to read the data.
-        self.pipe_handle.read_exact(&mut data_buffer)?;
+        self.channel.read_exact(&mut data_buffer)?;
 
         Ok(data_buffer)
     }
@@ -53,7 +144,57 @@ impl PreimageOracleClient for OracleReader {
             bail!("Buffer size {} does not match preimage size {}", buf.len(), length);
         }
 
-        self.pipe_handle.read_exact(buf)?;
+        self.channel.read_exact(buf)?;
+
+        Ok(())
+    }
+
+    /// Stream the data corresponding to the key from the host in chunks, without buffering the
+    /// whole preimage.
+    fn stream_into(
+        &mut self,
+        key: PreimageKey,
+        buf: &mut [u8],
+        sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        self.stream(key)?.read_into(buf, sink)
+    }
+}
+
+/// An [OracleServer] is a router for the host to serve data back to the client [OracleReader].
+#[derive(Debug, Clone, Copy)]
+pub struct OracleServer<C: Channel = PipeHandle> {
+    channel: C,
+}
+
+impl<C: Channel> OracleServer<C> {
+    /// Create a new [OracleServer] from a [Channel], such as a [PipeHandle].
+    pub fn new(channel: C) -> Self {
+        Self { channel }
+    }
+}
+
+impl<C: Channel> PreimageOracleServer for OracleServer<C> {
+    /// Read the next [PreimageKey] requested by the client, fetch the preimage, and write it back
+    /// to the client. The response is an 8-byte big-endian length prefix followed by the
+    /// preimage data.
+    fn next_preimage_request<F>(&self, fetcher: &mut F) -> Result<()>
+    where
+        F: PreimageFetcher,
+    {
+        // Read the preimage request from the client.
+        let mut buf = [0u8; 32];
+        self.channel.read_exact(&mut buf)?;
+        let preimage_key = PreimageKey::try_from(buf).map_err(|e| anyhow!(e))?;
+
+        // Fetch the preimage value from the preimage getter.
+        let value = fetcher.get_preimage(preimage_key)?;
+
+        // Write the length as a big-endian u64 followed by the data.
+        let mut data = Vec::with_capacity(value.len() + 8);
+        data.extend_from_slice(&(value.len() as u64).to_be_bytes());
+        data.extend_from_slice(&value);
+        self.channel.write_all(&data)?;
 
         Ok(())
     }
@@ -61,62 +202,123 @@ impl PreimageOracleClient for OracleReader {
 
 #[cfg(test)]
 mod test {
-    extern crate std;
-
     use super::*;
-    use crate::PreimageKeyType;
-    use kona_common::FileDescriptor;
-    use std::{fs::File, os::fd::AsRawFd};
-    use tempfile::tempfile;
-
-    /// Test struct containing the [OracleReader] and a [PipeHandle] for the host, plus the open
-    /// [File]s. The [File]s are stored in this struct so that they are not dropped until the
-    /// end of the test.
-    ///
-    /// TODO: Swap host pipe handle to oracle writer once it exists.
-    #[derive(Debug)]
-    struct ClientAndHost {
-        oracle_reader: OracleReader,
-        host_handle: PipeHandle,
-        _read_file: File,
-        _write_file: File,
+    use crate::{DuplexChannel, PreimageKeyType};
+    use alloc::boxed::Box;
+
+    /// A [PreimageFetcher] that serves a single preimage for a single key.
+    struct MockFetcher {
+        key: [u8; 32],
+        data: &'static [u8],
     }
 
-    /// Helper for creating a new [OracleReader] and [PipeHandle] for testing. The file channel is
-    /// over two temporary files.
-    ///
-    /// TODO: Swap host pipe handle to oracle writer once it exists.
-    fn client_and_host() -> ClientAndHost {
-        let (read_file, write_file) = (tempfile().unwrap(), tempfile().unwrap());
-        let (read_fd, write_fd) = (
-            FileDescriptor::Wildcard(read_file.as_raw_fd().try_into().unwrap()),
-            FileDescriptor::Wildcard(write_file.as_raw_fd().try_into().unwrap()),
-        );
-        let client_handle = PipeHandle::new(read_fd, write_fd);
-        let host_handle = PipeHandle::new(write_fd, read_fd);
+    impl PreimageFetcher for MockFetcher {
+        fn get_preimage(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+            let key: [u8; 32] = key.into();
+            if key != self.key {
+                bail!("Unknown preimage key");
+            }
+            Ok(self.data.to_vec())
+        }
+    }
 
-        let oracle_reader = OracleReader::new(client_handle);
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_oracle_reader() {
+        const MOCK_DATA: &[u8] = b"1234567890";
+        let key = PreimageKey::new([0xFFu8; 32], PreimageKeyType::Keccak256);
+        let (client_channel, host_channel) = DuplexChannel::pair();
+        let mut oracle_reader = OracleReader::new(client_channel);
+        let oracle_server = OracleServer::new(host_channel);
 
-        ClientAndHost { oracle_reader, host_handle, _read_file: read_file, _write_file: write_file }
+        let client = tokio::task::spawn(async move {
+            let data = oracle_reader.get(key).unwrap();
+            let mut buf = [0u8; MOCK_DATA.len()];
+            oracle_reader.get_exact(key, &mut buf).unwrap();
+            (data, buf)
+        });
+        let host = tokio::task::spawn(async move {
+            let mut fetcher = MockFetcher { key: key.into(), data: MOCK_DATA };
+            oracle_server.next_preimage_request(&mut fetcher).unwrap();
+            oracle_server.next_preimage_request(&mut fetcher).unwrap();
+        });
+
+        let (r, _) = tokio::join!(client, host);
+        let (data, buf) = r.unwrap();
+        assert_eq!(data, MOCK_DATA);
+        assert_eq!(buf, MOCK_DATA);
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_oracle_reader() {
+    async fn test_oracle_reader_stream_into() {
         const MOCK_DATA: &[u8] = b"1234567890";
-        let sys = client_and_host();
-        let (mut oracle_reader, host_handle) = (sys.oracle_reader, sys.host_handle);
+        let key = PreimageKey::new([0xFFu8; 32], PreimageKeyType::Keccak256);
+        let (client_channel, host_channel) = DuplexChannel::pair();
+        let oracle_server = OracleServer::new(host_channel);
 
         let client = tokio::task::spawn(async move {
-            oracle_reader.get(PreimageKey::new([0u8; 32], PreimageKeyType::Keccak256)).unwrap()
+            // Stream through the trait, as a generic client would.
+            let mut oracle: Box<dyn PreimageOracleClient + Send> =
+                Box::new(OracleReader::new(client_channel));
+            let mut streamed = Vec::new();
+            oracle
+                .stream_into(key, &mut [0u8; 4], &mut |chunk| {
+                    assert!(chunk.len() <= 4);
+                    streamed.extend_from_slice(chunk);
+                    Ok(())
+                })
+                .unwrap();
+            streamed
         });
         let host = tokio::task::spawn(async move {
-            let mut length_and_data: [u8; 8 + 10] = [0u8; 8 + 10];
-            length_and_data[0..8].copy_from_slice(&u64::to_be_bytes(MOCK_DATA.len() as u64));
-            length_and_data[8..18].copy_from_slice(MOCK_DATA);
-            host_handle.write(&length_and_data).unwrap();
+            let mut fetcher = MockFetcher { key: key.into(), data: MOCK_DATA };
+            oracle_server.next_preimage_request(&mut fetcher).unwrap();
         });
 
         let (r, _) = tokio::join!(client, host);
         assert_eq!(r.unwrap(), MOCK_DATA);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_oracle_reader_stream() {
+        const MOCK_DATA: &[u8] = b"1234567890";
+        let key = PreimageKey::new([0xFFu8; 32], PreimageKeyType::Keccak256);
+        let (client_channel, host_channel) = DuplexChannel::pair();
+        let mut oracle_reader = OracleReader::new(client_channel);
+        let oracle_server = OracleServer::new(host_channel);
+
+        let client = tokio::task::spawn(async move {
+            // Stream the body through a buffer smaller than the preimage.
+            let stream = oracle_reader.stream(key).unwrap();
+            assert_eq!(stream.len(), MOCK_DATA.len());
+            let mut streamed = Vec::new();
+            stream
+                .read_into(&mut [0u8; 3], |chunk| {
+                    streamed.extend_from_slice(chunk);
+                    Ok(())
+                })
+                .unwrap();
+
+            // Drop a partially read stream; the remainder must be drained.
+            let mut stream = oracle_reader.stream(key).unwrap();
+            let mut head = [0u8; 4];
+            assert_eq!(stream.read(&mut head).unwrap(), 4);
+            assert_eq!(stream.remaining(), MOCK_DATA.len() - 4);
+            drop(stream);
+
+            let data = oracle_reader.get(key).unwrap();
+            (streamed, head, data)
+        });
+        let host = tokio::task::spawn(async move {
+            let mut fetcher = MockFetcher { key: key.into(), data: MOCK_DATA };
+            for _ in 0..3 {
+                oracle_server.next_preimage_request(&mut fetcher).unwrap();
+            }
+        });
+
+        let (r, _) = tokio::join!(client, host);
+        let (streamed, head, data) = r.unwrap();
+        assert_eq!(streamed, MOCK_DATA);
+        assert_eq!(head, MOCK_DATA[..4]);
+        assert_eq!(data, MOCK_DATA);
+    }
 }
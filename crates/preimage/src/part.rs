@@ -0,0 +1,104 @@
+//! Contains the [PreimagePart] type, which mirrors the preimage parts served by the on-chain
+//! `PreimageOracle` contract's `readPreimage` function.
+
+use anyhow::{bail, Result};
+
+/// A [PreimagePart] is a 32-byte window into a preimage, as returned by the on-chain
+/// `PreimageOracle` contract's `readPreimage(key, offset)` function.
+///
+/// The on-chain oracle stores preimages with an 8-byte big-endian length prefix, and serves
+/// 32-byte parts of the prefixed preimage. Parts that extend past the end of the prefixed preimage
+/// are right-padded with zeros, and their [PreimagePart::len] is truncated accordingly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreimagePart {
+    /// The 32-byte part, right-padded with zeros.
+    data: [u8; 32],
+    /// The number of meaningful bytes in the part.
+    len: usize,
+}
+
+impl PreimagePart {
+    /// Create the [PreimagePart] of `preimage` at `offset`, where `offset` is relative to the
+    /// start of the 8-byte length prefix.
+    ///
+    /// # Returns
+    /// - `Ok(PreimagePart)` if `offset` is within the length-prefixed preimage.
+    /// - `Err(_)` if `offset >= preimage.len() + 8`, which the on-chain oracle rejects.
+    pub fn new(preimage: &[u8], offset: usize) -> Result<Self> {
+        let prefixed_len = preimage.len() + 8;
+        if offset >= prefixed_len {
+            bail!("Part offset {} out of bounds for preimage of size {}", offset, preimage.len());
+        }
+
+        let len = (prefixed_len - offset).min(32);
+        let mut data = [0u8; 32];
+        let length_prefix = (preimage.len() as u64).to_be_bytes();
+        for (i, byte) in data.iter_mut().take(len).enumerate() {
+            let pos = offset + i;
+            *byte = if pos < 8 { length_prefix[pos] } else { preimage[pos - 8] };
+        }
+
+        Ok(Self { data, len })
+    }
+
+    /// Returns the 32-byte part, right-padded with zeros.
+    pub fn data(&self) -> [u8; 32] {
+        self.data
+    }
+
+    /// Returns the number of meaningful bytes in the part. This is `32`, unless the part extends
+    /// past the end of the length-prefixed preimage.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the part contains no meaningful bytes. This never holds for a valid part.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_preimage_part() {
+        let preimage = (1..=40).collect::<Vec<u8>>();
+
+        // The first part includes the length prefix.
+        let part = PreimagePart::new(&preimage, 0).unwrap();
+        assert_eq!(part.len(), 32);
+        assert_eq!(part.data()[..8], 40u64.to_be_bytes());
+        assert_eq!(part.data()[8..], preimage[..24]);
+
+        // Parts in the middle of the preimage are full.
+        let part = PreimagePart::new(&preimage, 10).unwrap();
+        assert_eq!(part.len(), 32);
+        assert_eq!(part.data(), preimage[2..34]);
+
+        // The final part is truncated and right-padded.
+        let part = PreimagePart::new(&preimage, 40).unwrap();
+        assert_eq!(part.len(), 8);
+        assert_eq!(part.data()[..8], preimage[32..]);
+        assert_eq!(part.data()[8..], [0u8; 24]);
+
+        let part = PreimagePart::new(&preimage, 47).unwrap();
+        assert_eq!(part.len(), 1);
+        assert_eq!(part.data()[0], 40);
+
+        assert!(PreimagePart::new(&preimage, 48).is_err());
+    }
+
+    #[test]
+    fn test_empty_preimage_part() {
+        let part = PreimagePart::new(&[], 0).unwrap();
+        assert_eq!(part.len(), 8);
+        assert_eq!(part.data(), [0u8; 32]);
+
+        let part = PreimagePart::new(&[], 7).unwrap();
+        assert_eq!(part.len(), 1);
+        assert!(PreimagePart::new(&[], 8).is_err());
+    }
+}
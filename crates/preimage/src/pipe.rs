@@ -1,7 +1,8 @@
 //! This module contains a rudamentary pipe between two file descriptors, using [kona_common::io]
 //! for reading and writing from the file descriptors.
 
-use anyhow::{bail, Result};
+use crate::{traits::Channel, ChannelClosed};
+use anyhow::{anyhow, bail, Result};
 use kona_common::{io, FileDescriptor, RegisterSize};
 
 /// [PipeHandle] is a handle for one end of a bidirectional pipe.
@@ -25,10 +26,18 @@ impl PipeHandle {
     }
 
     /// Reads exactly `buf.len()` bytes into `buf`, blocking until all bytes are read.
+    ///
+    /// # Returns
+    /// - `Ok(RegisterSize)` with the number of bytes read.
+    /// - `Err(ChannelClosed)` if the pipe reached EOF before `buf` was filled.
+    /// - `Err(_)` if the pipe could not be read.
     pub fn read_exact(&self, buf: &mut [u8]) -> Result<RegisterSize> {
         let mut read = 0;
         while read < buf.len() {
             let chunk_read = self.read(&mut buf[read..])?;
+            if chunk_read == 0 {
+                return Err(anyhow!(ChannelClosed { read, len: buf.len() }));
+            }
             read += chunk_read as usize;
         }
         Ok(read as RegisterSize)
@@ -50,3 +59,43 @@ impl PipeHandle {
         Ok(written as RegisterSize)
     }
 }
+
+impl Channel for PipeHandle {
+    fn read_exact(&self, buf: &mut [u8]) -> Result<()> {
+        PipeHandle::read_exact(self, buf)?;
+        Ok(())
+    }
+
+    fn write_all(&self, buf: &[u8]) -> Result<()> {
+        self.write(buf)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use super::*;
+    use std::{
+        io::{Seek, SeekFrom, Write},
+        os::fd::AsRawFd,
+    };
+
+    #[test]
+    fn test_read_exact_eof() {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(&[0xAA]).unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+
+        let fd = FileDescriptor::Wildcard(file.as_raw_fd() as RegisterSize);
+        let pipe = PipeHandle::new(fd, fd);
+        let mut buf = [0u8; 2];
+        let err = pipe.read_exact(&mut buf).unwrap_err();
+        assert_eq!(err.downcast_ref::<ChannelClosed>(), Some(&ChannelClosed { read: 1, len: 2 }));
+        assert_eq!(buf[0], 0xAA);
+
+        let err = Channel::read_exact(&pipe, &mut buf).unwrap_err();
+        assert_eq!(err.downcast_ref::<ChannelClosed>(), Some(&ChannelClosed { read: 0, len: 2 }));
+    }
+}
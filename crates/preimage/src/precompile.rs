@@ -0,0 +1,133 @@
+//! Contains utilities for accelerating precompile calls by running them natively on the host.
+//!
+//! Running expensive precompiles such as `ecrecover`, `bn256Pairing`, or the KZG point evaluation
+//! precompile inside of an FPVM costs billions of instructions. Instead, the client hints the
+//! precompile call to the host, which runs it natively and serves the result under a
+//! [PreimageKeyType::Precompile] key.
+//!
+//! [PreimageKeyType::Precompile]: crate::PreimageKeyType::Precompile
+
+use crate::{Hint, HintType, HintWriterClient, PreimageKey, PreimageOracleClient};
+use alloc::vec::Vec;
+use anyhow::{bail, Result};
+
+/// The result of a precompile call that was run on the host.
+///
+/// **Encoding**: the preimage served by the host is a 1-byte status flag (`1` for success, `0` for
+/// failure) followed by the precompile's output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecompileOutput {
+    /// Whether the precompile call succeeded.
+    pub success: bool,
+    /// The output of the precompile call.
+    pub output: Vec<u8>,
+}
+
+impl PrecompileOutput {
+    /// Create a new [PrecompileOutput].
+    pub fn new(success: bool, output: Vec<u8>) -> Self {
+        Self { success, output }
+    }
+
+    /// Encodes the [PrecompileOutput] into the preimage format served by the host.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.output.len() + 1);
+        buf.push(self.success as u8);
+        buf.extend_from_slice(&self.output);
+        buf
+    }
+
+    /// Decodes a [PrecompileOutput] from the preimage format served by the host.
+    pub fn decode(buf: &[u8]) -> Result<Self> {
+        let Some((status, output)) = buf.split_first() else {
+            bail!("Empty precompile result");
+        };
+        let success = match status {
+            0 => false,
+            1 => true,
+            _ => bail!("Invalid precompile result status: {status}"),
+        };
+        Ok(Self { success, output: output.to_vec() })
+    }
+}
+
+/// Calls the precompile at `address` with `input` by hinting the call to the host and reading the
+/// result back from the oracle under its [PreimageKeyType::Precompile] key.
+///
+/// [PreimageKeyType::Precompile]: crate::PreimageKeyType::Precompile
+pub fn call_precompile<O, H>(
+    oracle: &mut O,
+    hint_writer: &H,
+    address: &[u8; 20],
+    input: &[u8],
+) -> Result<PrecompileOutput>
+where
+    O: PreimageOracleClient + ?Sized,
+    H: HintWriterClient + ?Sized,
+{
+    hint_writer.write_hint(&Hint::new(HintType::L1Precompile, [address, input].concat()))?;
+    let result = oracle.get(PreimageKey::new_precompile(address, input))?;
+    PrecompileOutput::decode(&result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Hint, PreimageKeyType};
+    use alloc::{string::String, vec};
+    use core::cell::RefCell;
+
+    /// A mock host that serves the hinted precompile call, echoing its input as the output.
+    #[derive(Default)]
+    struct MockHost {
+        hint: RefCell<Option<String>>,
+    }
+
+    impl HintWriterClient for MockHost {
+        fn write(&self, hint: &str) -> Result<()> {
+            self.hint.replace(Some(String::from(hint)));
+            Ok(())
+        }
+    }
+
+    impl PreimageOracleClient for &MockHost {
+        fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+            let hint = Hint::parse(self.hint.borrow().as_ref().unwrap())?;
+            assert_eq!(hint.hint_type, HintType::L1Precompile);
+
+            let (address, input) = hint.data.split_at(20);
+            let expected_key = PreimageKey::new_precompile(address.try_into()?, input);
+            assert_eq!(<[u8; 32]>::from(key), <[u8; 32]>::from(expected_key));
+            assert_eq!(key.key_type(), PreimageKeyType::Precompile);
+
+            Ok(PrecompileOutput::new(true, input.to_vec()).encode())
+        }
+
+        fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+            let preimage = self.get(key)?;
+            if preimage.len() != buf.len() {
+                anyhow::bail!("Expected {} bytes, got {}", buf.len(), preimage.len());
+            }
+            buf.copy_from_slice(&preimage);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_call_precompile() {
+        let host = MockHost::default();
+        let address = [0x01; 20];
+        let result = call_precompile(&mut &host, &host, &address, &[0xAA, 0xBB]).unwrap();
+        assert_eq!(result, PrecompileOutput::new(true, vec![0xAA, 0xBB]));
+    }
+
+    #[test]
+    fn test_precompile_output_roundtrip() {
+        for success in [true, false] {
+            let output = PrecompileOutput::new(success, vec![1, 2, 3]);
+            assert_eq!(PrecompileOutput::decode(&output.encode()).unwrap(), output);
+        }
+        assert!(PrecompileOutput::decode(&[]).is_err());
+        assert!(PrecompileOutput::decode(&[2]).is_err());
+    }
+}
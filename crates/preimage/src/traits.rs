@@ -1,6 +1,25 @@
-use crate::PreimageKey;
+use crate::{Hint, PreimageKey};
 use alloc::vec::Vec;
-use anyhow::Result;
+use anyhow::{bail, Result};
+
+/// A [Channel] is a bidirectional byte stream that the preimage and hint protocols are spoken over.
+/// Implementations include the [crate::PipeHandle] over the FPVM's file descriptors, and the
+/// in-memory [crate::DuplexChannel].
+pub trait Channel {
+    /// Reads exactly `buf.len()` bytes into `buf`, blocking until all bytes are read.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the buffer was filled.
+    /// - `Err(_)` if the channel could not be read from.
+    fn read_exact(&self, buf: &mut [u8]) -> Result<()>;
+
+    /// Writes the entirety of `buf` to the channel, blocking until all bytes are written.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the buffer was fully written.
+    /// - `Err(_)` if the channel could not be written to.
+    fn write_all(&self, buf: &[u8]) -> Result<()>;
+}
 
 /// A [PreimageOracleClient] is a high-level interface to read data from the host, keyed by a
 /// [PreimageKey].
@@ -20,6 +39,43 @@ pub trait PreimageOracleClient {
     /// - `Ok(())` if the data was successfully written into the buffer.
     /// - `Err(_)` if the data could not be written into the buffer.
     fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()>;
+
+    /// Get the data corresponding to the key from the host, passing it to `sink` in chunks of up
+    /// to `buf.len()` bytes, so that large preimages never have to be held in memory in their
+    /// entirety.
+    ///
+    /// The provided implementation fetches the whole preimage with [PreimageOracleClient::get]
+    /// before chunking it. Oracles that can read the body incrementally, such as
+    /// [crate::OracleReader], override it.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the data was read in its entirety and accepted by the sink.
+    /// - `Err(_)` if the data could not be fetched from the host, or the sink returned an error.
+    fn stream_into(
+        &mut self,
+        key: PreimageKey,
+        buf: &mut [u8],
+        sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let data = self.get(key)?;
+        stream_slice(&data, buf, sink)
+    }
+}
+
+/// Passes `data` to `sink` in chunks of up to `buf.len()` bytes, copying each chunk through `buf`.
+pub(crate) fn stream_slice(
+    data: &[u8],
+    buf: &mut [u8],
+    sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+) -> Result<()> {
+    if buf.is_empty() && !data.is_empty() {
+        bail!("Cannot stream a preimage into an empty buffer");
+    }
+    for chunk in data.chunks(buf.len().max(1)) {
+        buf[..chunk.len()].copy_from_slice(chunk);
+        sink(&buf[..chunk.len()])?;
+    }
+    Ok(())
 }
 
 /// A [HintWriterClient] is a high-level interface to the hint pipe. It provides a way to write
@@ -32,4 +88,65 @@ pub trait HintWriterClient {
     /// - `Ok(())` if the hint was successfully written to the host.
     /// - `Err(_)` if the hint could not be written to the host.
     fn write(&self, hint: &str) -> Result<()>;
+
+    /// Write a typed [Hint] to the host, encoded with [Hint::encode].
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was successfully written to the host.
+    /// - `Err(_)` if the hint could not be written to the host.
+    fn write_hint(&self, hint: &Hint) -> Result<()> {
+        self.write(&hint.encode())
+    }
+}
+
+/// A [PreimageFetcher] is a pluggable source of preimages for the host. It is queried by a
+/// [PreimageOracleServer] for every key that the client requests.
+pub trait PreimageFetcher {
+    /// Fetch the preimage for the given [PreimageKey].
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)` if the preimage was successfully fetched.
+    /// - `Err(_)` if the preimage could not be fetched.
+    fn get_preimage(&mut self, key: PreimageKey) -> Result<Vec<u8>>;
+}
+
+/// A [PreimageOracleServer] is a high-level interface to accept read requests from the client and
+/// write the preimage data back to the client pipe.
+pub trait PreimageOracleServer {
+    /// Get the next preimage request from the client, fetch the preimage with the passed
+    /// [PreimageFetcher], and write it back to the client. Blocks until the request has been
+    /// fully served.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the request was successfully served.
+    /// - `Err(_)` if the request could not be read, fetched, or written back to the client.
+    fn next_preimage_request<F>(&self, fetcher: &mut F) -> Result<()>
+    where
+        F: PreimageFetcher;
+}
+
+/// A [HintRouter] is a host-side destination for hints received from the client. It is invoked by
+/// a [HintReaderServer] for every hint that the client writes.
+pub trait HintRouter {
+    /// Route a hint to the appropriate handler. The hint is not acknowledged to the client until
+    /// this method returns.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was successfully handled.
+    /// - `Err(_)` if the hint could not be handled.
+    fn route_hint(&mut self, hint: &str) -> Result<()>;
+}
+
+/// A [HintReaderServer] is a high-level interface to read hints from the client and route them to
+/// the host's hint handlers.
+pub trait HintReaderServer {
+    /// Get the next hint from the client, route it with the passed [HintRouter], and acknowledge
+    /// it once it has been handled. Blocks until the hint has been fully processed.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was successfully read and routed.
+    /// - `Err(_)` if the hint could not be read, routed, or acknowledged.
+    fn next_hint<R>(&self, router: &mut R) -> Result<()>
+    where
+        R: HintRouter;
 }
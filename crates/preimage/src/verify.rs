@@ -0,0 +1,192 @@
+//! Contains the [VerifyingOracle], a [PreimageOracleClient] wrapper that checks the preimages
+//! returned by the host against their keys.
+
+use crate::{PreimageKey, PreimageKeyType, PreimageOracleClient};
+use alloc::vec::Vec;
+use anyhow::{anyhow, Result};
+use core::fmt::Display;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+/// An error returned by the [VerifyingOracle] when a preimage does not match its key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreimageVerificationError {
+    /// The digest of the preimage returned by the host does not match the [PreimageKey] it was
+    /// requested with. The first argument is the requested key, and the second argument is the
+    /// digest of the returned preimage.
+    DigestMismatch(PreimageKey, [u8; 32]),
+}
+
+impl Display for PreimageVerificationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PreimageVerificationError::DigestMismatch(key, digest) => {
+                let key: [u8; 32] = (*key).into();
+                write!(
+                    f,
+                    "Preimage digest mismatch: key 0x{}, got digest 0x{}",
+                    hex::encode(key),
+                    hex::encode(digest)
+                )
+            }
+        }
+    }
+}
+
+/// A [VerifyingOracle] wraps a [PreimageOracleClient] and verifies that the preimages it returns
+/// hash to the key they were requested with. Only [PreimageKeyType::Keccak256] and
+/// [PreimageKeyType::Sha256] keys can be verified; all other key types are passed through as-is.
+///
+/// Verification failures are returned as a [PreimageVerificationError], which can be recovered
+/// from the [anyhow::Error] with [anyhow::Error::downcast_ref].
+#[derive(Debug, Clone)]
+pub struct VerifyingOracle<O: PreimageOracleClient> {
+    inner: O,
+}
+
+impl<O: PreimageOracleClient> VerifyingOracle<O> {
+    /// Create a new [VerifyingOracle] that wraps the given [PreimageOracleClient].
+    pub fn new(inner: O) -> Self {
+        Self { inner }
+    }
+
+    /// Consumes the [VerifyingOracle], returning the inner [PreimageOracleClient].
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+
+    /// Verifies that the digest of `data` matches the low-order 31 bytes of `key`.
+    fn verify(key: PreimageKey, data: &[u8]) -> Result<()> {
+        let digest: [u8; 32] = match key.key_type() {
+            PreimageKeyType::Keccak256 => Keccak256::digest(data).into(),
+            PreimageKeyType::Sha256 => Sha256::digest(data).into(),
+            _ => return Ok(()),
+        };
+        Self::check_digest(key, digest)
+    }
+
+    /// Checks that `digest` matches the low-order 31 bytes of `key`.
+    fn check_digest(key: PreimageKey, digest: [u8; 32]) -> Result<()> {
+        let rendered_key: [u8; 32] = key.into();
+        if rendered_key[1..] != digest[1..] {
+            return Err(anyhow!(PreimageVerificationError::DigestMismatch(key, digest)));
+        }
+        Ok(())
+    }
+}
+
+impl<O: PreimageOracleClient> PreimageOracleClient for VerifyingOracle<O> {
+    fn get(&mut self, key: PreimageKey) -> Result<Vec<u8>> {
+        let data = self.inner.get(key)?;
+        Self::verify(key, &data)?;
+        Ok(data)
+    }
+
+    fn get_exact(&mut self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        self.inner.get_exact(key, buf)?;
+        Self::verify(key, buf)
+    }
+
+    /// Streams the preimage from the inner oracle, hashing it as it passes through. Chunks reach
+    /// the sink before the preimage can be verified, so callers must discard everything they
+    /// received if an error is returned.
+    fn stream_into(
+        &mut self,
+        key: PreimageKey,
+        buf: &mut [u8],
+        sink: &mut dyn FnMut(&[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let key_type = key.key_type();
+        let (mut keccak, mut sha256) = (Keccak256::new(), Sha256::new());
+        self.inner.stream_into(key, buf, &mut |chunk| {
+            match key_type {
+                PreimageKeyType::Keccak256 => keccak.update(chunk),
+                PreimageKeyType::Sha256 => sha256.update(chunk),
+                _ => {}
+            }
+            sink(chunk)
+        })?;
+
+        let digest: [u8; 32] = match key_type {
+            PreimageKeyType::Keccak256 => keccak.finalize().into(),
+            PreimageKeyType::Sha256 => sha256.finalize().into(),
+            _ => return Ok(()),
+        };
+        Self::check_digest(key, digest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+
+    /// A [PreimageOracleClient] that always returns the same preimage, regardless of the key.
+    struct ConstantOracle(&'static [u8]);
+
+    impl PreimageOracleClient for ConstantOracle {
+        fn get(&mut self, _: PreimageKey) -> Result<Vec<u8>> {
+            Ok(self.0.to_vec())
+        }
+
+        fn get_exact(&mut self, _: PreimageKey, buf: &mut [u8]) -> Result<()> {
+            buf.copy_from_slice(self.0);
+            Ok(())
+        }
+    }
+
+    const PREIMAGE: &[u8] = b"hello world";
+
+    #[test]
+    fn test_verifying_oracle_valid() {
+        let mut oracle = VerifyingOracle::new(ConstantOracle(PREIMAGE));
+
+        let keccak_key =
+            PreimageKey::new(Keccak256::digest(PREIMAGE).into(), PreimageKeyType::Keccak256);
+        assert_eq!(oracle.get(keccak_key).unwrap(), PREIMAGE);
+
+        let sha_key = PreimageKey::new(Sha256::digest(PREIMAGE).into(), PreimageKeyType::Sha256);
+        let mut buf = vec![0u8; PREIMAGE.len()];
+        oracle.get_exact(sha_key, &mut buf).unwrap();
+        assert_eq!(buf, PREIMAGE);
+
+        // Local keys can't be verified, and are passed through.
+        assert_eq!(oracle.get(PreimageKey::new_local(0)).unwrap(), PREIMAGE);
+    }
+
+    #[test]
+    fn test_verifying_oracle_mismatch() {
+        let mut oracle = VerifyingOracle::new(ConstantOracle(PREIMAGE));
+
+        for key_type in [PreimageKeyType::Keccak256, PreimageKeyType::Sha256] {
+            let key = PreimageKey::new([0xFFu8; 32], key_type);
+            let err = oracle.get(key).unwrap_err();
+            let Some(PreimageVerificationError::DigestMismatch(err_key, _)) =
+                err.downcast_ref::<PreimageVerificationError>()
+            else {
+                panic!("Expected a digest mismatch error");
+            };
+            assert_eq!(<[u8; 32]>::from(*err_key), <[u8; 32]>::from(key));
+        }
+    }
+
+    #[test]
+    fn test_verifying_oracle_stream() {
+        let mut oracle = VerifyingOracle::new(ConstantOracle(PREIMAGE));
+
+        let keccak_key =
+            PreimageKey::new(Keccak256::digest(PREIMAGE).into(), PreimageKeyType::Keccak256);
+        let mut streamed = Vec::new();
+        oracle
+            .stream_into(keccak_key, &mut [0u8; 4], &mut |chunk| {
+                streamed.extend_from_slice(chunk);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(streamed, PREIMAGE);
+
+        let bad_key = PreimageKey::new([0xFFu8; 32], PreimageKeyType::Sha256);
+        let err = oracle.stream_into(bad_key, &mut [0u8; 4], &mut |_| Ok(())).unwrap_err();
+        assert!(err.downcast_ref::<PreimageVerificationError>().is_some());
+    }
+}